@@ -13,6 +13,26 @@ fn compile_rule(rule: &str) -> matchingram::result::Result<matchingram::Matcher>
     matchingram::compile_rule(rule)
 }
 
+// 生成包含大量关键字的规则，关键字均不会出现在测试消息中（最坏情况）。
+fn keywords_rule(operator: &str, count: usize) -> String {
+    let keywords = (0..count)
+        .map(|i| format!(r#""关键字{}""#, i))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!("(message.text {} {{{}}})", operator, keywords)
+}
+
+// 逐个关键字调用 `contains` 的朴素实现，作为自动机的对照。
+fn contains_any(text: &str, keywords: &[String]) -> bool {
+    keywords.iter().any(|k| text.contains(k.as_str()))
+}
+
+// 逐个比较的朴素实现，作为整数集合的对照。
+fn contains_id(ids: &[i64], id: i64) -> bool {
+    ids.iter().any(|i| *i == id)
+}
+
 fn load_data_file(fname: &str) -> Vec<u8> {
     use std::env;
     use std::fs::read;
@@ -91,6 +111,69 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("rule_match longer-rule", |b| {
         b.iter(|| rule_match(black_box(long_rule)))
     });
+
+    let message = matchingram::models::Message {
+        text: Some(MESSAGE_TEST.to_owned()),
+        ..Default::default()
+    };
+    let keywords = (0..500).map(|i| format!("关键字{}", i)).collect::<Vec<_>>();
+    let any_matcher = compile_rule(&keywords_rule("any", 500)).unwrap();
+    let all_matcher = compile_rule(&keywords_rule("all", 500)).unwrap();
+    // 关键字达到 16 个时才编译自动机，15 个关键字的条件仍然逐个查找，两者可以直接对照。
+    let any_15_matcher = compile_rule(&keywords_rule("any", 15)).unwrap();
+    let any_16_matcher = compile_rule(&keywords_rule("any", 16)).unwrap();
+
+    assert!(!contains_any(MESSAGE_TEST, &keywords));
+    assert!(matches!(any_matcher.match_message(&message), Ok(false)));
+    assert!(matches!(all_matcher.match_message(&message), Ok(false)));
+    assert!(matches!(any_15_matcher.match_message(&message), Ok(false)));
+    assert!(matches!(any_16_matcher.match_message(&message), Ok(false)));
+
+    c.bench_function("contains_any 500-keywords", |b| {
+        b.iter(|| contains_any(black_box(MESSAGE_TEST), black_box(&keywords)))
+    });
+    c.bench_function("matcher_match any-500-keywords", |b| {
+        b.iter(|| any_matcher.match_message(black_box(&message)))
+    });
+    c.bench_function("matcher_match all-500-keywords", |b| {
+        b.iter(|| all_matcher.match_message(black_box(&message)))
+    });
+    c.bench_function("matcher_match any-15-keywords", |b| {
+        b.iter(|| any_15_matcher.match_message(black_box(&message)))
+    });
+    c.bench_function("matcher_match any-16-keywords", |b| {
+        b.iter(|| any_16_matcher.match_message(black_box(&message)))
+    });
+
+    // 数量较多的 ID 会在编译时构建为哈希集合。
+    let id_message = matchingram::models::Message {
+        from: Some(matchingram::models::User {
+            id: 1,
+            is_bot: false,
+            first_name: "Bot".to_owned(),
+            last_name: None,
+            username: None,
+            language_code: None,
+        }),
+        ..Default::default()
+    };
+    let ids = (10000..20000).collect::<Vec<i64>>();
+    let ids_value = ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let in_matcher = compile_rule(&format!("(message.from.id in {{{}}})", ids_value)).unwrap();
+
+    assert!(!contains_id(&ids, 1));
+    assert!(matches!(in_matcher.match_message(&id_message), Ok(false)));
+
+    c.bench_function("contains_id 10000-ids", |b| {
+        b.iter(|| contains_id(black_box(&ids), black_box(1)))
+    });
+    c.bench_function("matcher_match in-10000-ids", |b| {
+        b.iter(|| in_matcher.match_message(black_box(&id_message)))
+    });
     c.bench_function("compile_rule 1mb-rule", |b| {
         b.iter(|| compile_rule(black_box(size_1mb_rule)))
     });
@@ -0,0 +1,200 @@
+//! 面向规则作者的编译诊断。
+//!
+//! 将附带位置的错误渲染为类似 rustc 的格式，指出出错的行、列并给出提示：
+//! ```text
+//! error: unknown operator `eqq`
+//!  --> 2:16
+//!   |
+//! 2 |   message.text eqq "hello"
+//!   |                ^^^
+//!   = hint: did you mean `eq`?
+//! ```
+
+use std::fmt;
+use strum::IntoEnumIterator;
+use unicode_width::UnicodeWidthChar;
+
+use super::error::{supported_operators, Error};
+use super::operator::Operator;
+
+/// 一条编译诊断。
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Diagnostic {
+    /// 错误描述。
+    pub message: String,
+    /// 出错位置所在的行，从 1 开始。
+    pub line: usize,
+    /// 出错位置所在的列，从 1 开始，以字符为单位。
+    pub column: usize,
+    /// 出错的整行规则文本。
+    pub source_line: String,
+    /// 出错范围在当前行中的字符数量，至少为 1。
+    pub len: usize,
+    /// 修正建议。
+    pub hint: Option<String>,
+}
+
+impl Diagnostic {
+    /// 从规则和编译规则时产生的错误创建诊断。错误不包含位置信息时返回 `None`。
+    pub fn new(rule: &str, error: &Error) -> Option<Self> {
+        let span = error.span()?;
+        let (line, column) = (span.line, span.column);
+        let source_line = rule.lines().nth(line - 1).unwrap_or("").to_owned();
+        let rest = source_line.chars().count().saturating_sub(column - 1);
+        let len = span.end.saturating_sub(span.begin).min(rest).max(1);
+
+        Some(Self {
+            message: message(error.inner()),
+            line,
+            column,
+            source_line,
+            len,
+            hint: hint(error.inner()),
+        })
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gutter = " ".repeat(self.line.to_string().len());
+        // 保留制表符，使标记与源码对齐。
+        let padding = self
+            .source_line
+            .chars()
+            .take(self.column - 1)
+            .map(|c| match c {
+                '\t' => "\t".to_owned(),
+                c => " ".repeat(c.width().unwrap_or(0)),
+            })
+            .collect::<String>();
+        let carets = self
+            .source_line
+            .chars()
+            .skip(self.column - 1)
+            .take(self.len)
+            .map(|c| c.width().unwrap_or(0))
+            .sum::<usize>()
+            .max(1);
+
+        writeln!(f, "error: {}", self.message)?;
+        writeln!(f, "{}--> {}:{}", gutter, self.line, self.column)?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", self.line, self.source_line)?;
+        write!(f, "{} | {}{}", gutter, padding, "^".repeat(carets))?;
+        if let Some(hint) = &self.hint {
+            write!(f, "\n{} = hint: {}", gutter, hint)?;
+        }
+
+        Ok(())
+    }
+}
+
+// 不包含列号的错误描述，位置已经由诊断本身给出。
+fn message(error: &Error) -> String {
+    use Error::*;
+
+    match error {
+        ShouldEndHere { .. } => "unexpected content after the end of the rule".to_owned(),
+        ShouldCloseParenthesisHere { .. } => "expected `)`".to_owned(),
+        MissingField { .. } => "missing field".to_owned(),
+        MissingOperator { .. } => "missing operator".to_owned(),
+        MissingValue { .. } => "missing value".to_owned(),
+        MissingQuote { .. } => "unterminated string".to_owned(),
+        MissingCommentEnd { .. } => "unterminated block comment".to_owned(),
+        InvalidEscape { .. } => "invalid escape sequence".to_owned(),
+        ShouldQuoteHere { .. } => "expected `\"`".to_owned(),
+        ShouldCloseBraceHere { .. } => "expected `}`".to_owned(),
+        ShouldValueHere { .. } => "expected a value".to_owned(),
+        ShouldOpenBraceOrQuote { .. } => "expected `{` or `\"`".to_owned(),
+        MissingCondition { .. } => "missing condition".to_owned(),
+        IntegerParseFailed { .. } => "invalid integer".to_owned(),
+        DecimalParseFailed { .. } => "invalid decimal number".to_owned(),
+        ParseFailed { .. } => "invalid token".to_owned(),
+        UnknownField { field, .. } => format!("unknown field `{}`", field),
+        UnknownOperator { operator, .. } => format!("unknown operator `{}`", operator),
+        UnsupportedOperator { field, operator } => format!(
+            "the field `{}` does not support the `{}` operator",
+            field.to_string(),
+            operator.to_string()
+        ),
+        UnknownModifier { modifier } => format!("unknown modifier `{}`", modifier),
+        e => e.to_string(),
+    }
+}
+
+fn hint(error: &Error) -> Option<String> {
+    use Error::*;
+
+    // 拼写建议和字段支持的运算符优先于通用的提示。
+    match error {
+        UnknownField {
+            suggestion: Some(suggestion),
+            ..
+        }
+        | UnknownOperator {
+            suggestion: Some(suggestion),
+            ..
+        } => return Some(format!("did you mean `{}`?", suggestion)),
+        UnsupportedOperator { field, .. } => return Some(supported_operators(field)),
+        // 缺失的值是单个值还是列表，取决于运算符。
+        MissingValue {
+            operator: Some(operator),
+            ..
+        } => {
+            let example = match operator {
+                Operator::Between => "a range like `1..10`",
+                o if o.takes_list() => "a value or a list like `{\"a\" \"b\"}`",
+                _ => "a single value like `\"a\"` or `1`",
+            };
+
+            return Some(format!("`{}` takes {}", operator.to_string(), example));
+        }
+        UnknownOperator { .. } => {
+            let operators = Operator::iter()
+                .map(|o| format!("`{}`", o.to_string()))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            return Some(format!("available operators are {}", operators));
+        }
+        _ => (),
+    }
+
+    let hint = match error {
+        ShouldCloseParenthesisHere { .. } => "every group must be closed with `)`",
+        ShouldEndHere { .. } => "conditions must be joined with `and` or `or`",
+        MissingQuote { .. } => "add the closing `\"`",
+        MissingCommentEnd { .. } => "close the comment with `*/`",
+        InvalidEscape { .. } => "supported escapes are `\\\"`, `\\\\`, `\\n`, `\\t` and `\\u{...}`",
+        ShouldCloseBraceHere { .. } | ShouldValueHere { .. } => "a list looks like `{\"a\" \"b\"}`",
+        ShouldOpenBraceOrQuote { .. } => "wrap strings in `\"`, or lists in `{` and `}`",
+        MissingOperator { .. } => "add an operator such as `eq` or `any` after the field",
+        MissingValue { .. } => "add a value after the operator",
+        MissingCondition { .. } => "add a condition like `message.text eq \"...\"`",
+        UnknownField { .. } => "see the README for the list of supported fields",
+        FieldNotEndabled { .. } => "this field is not enabled yet",
+        UnknownModifier { .. } => "available modifiers are `i` and `nfkc`",
+        UnsupportedModifier { .. } => {
+            "modifiers only apply to `eq`, `in`, `any`, `all`, `hd` and `td` on text fields"
+        }
+        FieldRequireOperator { .. } | FieldRequireValue { .. } => {
+            "this field must be compared with a value, like `field eq \"...\"`"
+        }
+        NotAString { .. } => "strings must be wrapped in `\"`",
+        NotAnInteger { .. } => "this field requires an integer",
+        NotADecimal { .. } => "this field requires a decimal number",
+        NotABoolean { .. } => "this field requires `true` or `false`",
+        NotARange { .. } => "a range looks like `1..10`",
+        InvalidRange { .. } => "put the smaller number first, like `1..10`",
+        UnsupportedList { .. } => "named lists only apply to `in`, `any`, `all` and `domain_in`",
+        InvalidDomain { .. } => {
+            "use a domain like `example.com`, or `*.example.com` for its subdomains"
+        }
+        InvalidRegex { .. } => "check the regular expression syntax",
+        ExpectedSingleValue { .. } => "remove the `{` and `}` and keep a single value",
+        EmptyList { .. } => "add at least one value to the list",
+        _ => return None,
+    };
+
+    Some(hint.to_owned())
+}
@@ -1,6 +1,6 @@
 //! 全部可能出现的错误。
 
-use super::lexer::Token;
+use super::lexer::{Position, Token};
 use super::matches::{Field, Value};
 use super::operator::Operator;
 use thiserror::Error;
@@ -9,23 +9,23 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 pub enum Error {
     /// 应该在这里结束。
-    #[error("it should end here (--> {column:?})")]
-    ShouldEndHere { column: usize },
+    #[error("it should end here (--> {position})")]
+    ShouldEndHere { position: Position },
 
     /// 应该是开启的小括号。
     #[error("it should be `(` (--> {column:?})")]
     ShouldOpenParenthesisHere { column: usize },
 
     /// 应该是关闭的小括号。
-    #[error("it should be `)` (--> {column:?})")]
-    ShouldCloseParenthesisHere { column: usize },
+    #[error("it should be `)` (--> {position})")]
+    ShouldCloseParenthesisHere { position: Position },
 
     /// 缺失 token 位置信息。
     #[error("there may be a bug: missing token location data (index: {index:?})")]
     MissingPosition { index: usize },
 
     /// 不支持的运算符。
-    #[error("the field `{}` does not support the `{}` operator", field.to_string(), operator.to_string())]
+    #[error("the field `{}` does not support the `{}` operator, {}", field.to_string(), operator.to_string(), supported_operators(field))]
     UnsupportedOperator { field: Field, operator: Operator },
 
     /// 字段未被启用。
@@ -33,24 +33,38 @@ pub enum Error {
     FieldNotEndabled { field: Field },
 
     /// 未知的字段。
-    #[error("unknown `{field:?}` field")]
-    UnknownField { field: String },
+    #[error("unknown field `{field}`{}", did_you_mean(suggestion))]
+    UnknownField {
+        field: String,
+        suggestion: Option<String>,
+    },
 
     /// 未知的操作符。
-    #[error("unknown `{operator:?}` operator")]
-    UnknownOperator { operator: String },
+    #[error("unknown operator `{operator}`{}", did_you_mean(suggestion))]
+    UnknownOperator {
+        operator: String,
+        suggestion: Option<String>,
+    },
+
+    /// 未知的修饰符。
+    #[error("unknown `{modifier:?}` modifier")]
+    UnknownModifier { modifier: String },
+
+    /// 不支持修饰符。
+    #[error("the `{}` operator of the field `{}` does not support modifiers", operator.to_string(), field.to_string())]
+    UnsupportedModifier { field: Field, operator: Operator },
 
     /// 不合法的值。
     #[error("the value `{value:?}` of the field `{field:?}` is invalid")]
     InvalidValue { value: String, field: String },
 
     /// 缺失字段。
-    #[error("missing field from column {column:?}")]
-    MissingField { column: usize },
+    #[error("missing field from {position}")]
+    MissingField { position: Position },
 
     /// 解析中缺失操作符。
-    #[error("missing operator from column {column:?}")]
-    MissingOperator { column: usize },
+    #[error("missing operator from {position}")]
+    MissingOperator { position: Position },
 
     /// 字段需要运算符。
     #[error("field `{}` requires operator", field.to_string())]
@@ -60,32 +74,43 @@ pub enum Error {
     #[error("field `{}` requires value", field.to_string())]
     FieldRequireValue { field: Field },
 
-    /// 缺失值。
-    #[error("missing value from column {column:?}")]
-    MissingValue { column: usize },
+    /// 缺失值。`operator` 是缺失值的运算符，用于给出符合运算符的提示。
+    #[error("missing value from {position}")]
+    MissingValue {
+        position: Position,
+        operator: Option<Operator>,
+    },
+
+    #[error("missing quote from {position}")]
+    MissingQuote { position: Position },
+
+    /// 未闭合的块注释。
+    #[error("unterminated block comment from {position}")]
+    MissingCommentEnd { position: Position },
 
-    #[error("missing quote from column {column:?}")]
-    MissingQuote { column: usize },
+    /// 不合法的转义序列。
+    #[error("invalid escape sequence from {position}")]
+    InvalidEscape { position: Position },
 
     /// 应该是引号。
-    #[error("should be `\"` from column: {column:?}")]
-    ShouldQuoteHere { column: usize },
+    #[error("should be `\"` from {position}")]
+    ShouldQuoteHere { position: Position },
 
     /// 应该是关闭的大括号。
-    #[error("should be `}}` from column: {column:?}")]
-    ShouldCloseBraceHere { column: usize },
+    #[error("should be `}}` from {position}")]
+    ShouldCloseBraceHere { position: Position },
 
     /// 应该是值。
-    #[error("should be values from column: {column:?}")]
-    ShouldValueHere { column: usize },
+    #[error("should be values from {position}")]
+    ShouldValueHere { position: Position },
 
     /// 应该是打开的大括号或引号。
-    #[error("should be `{{` or `\"` from column: {column:?}")]
-    ShouldOpenBraceOrQuote { column: usize },
+    #[error("should be `{{` or `\"` from {position}")]
+    ShouldOpenBraceOrQuote { position: Position },
 
     /// 缺失条件。
-    #[error("missing condition from column {column:?}")]
-    MissingCondition { column: usize },
+    #[error("missing condition from {position}")]
+    MissingCondition { position: Position },
 
     /// 位置推断失败。
     #[error("failed to infer position from token `{token:?}`")]
@@ -100,16 +125,16 @@ pub enum Error {
     MissingTokenData { index: usize },
 
     /// 整数转换出错。
-    #[error("error in conversion of integer numbers starting in column {column:?}")]
-    IntegerParseFailed { column: usize },
+    #[error("error in conversion of integer numbers starting in {position}")]
+    IntegerParseFailed { position: Position },
 
     /// 小数转换出错。
-    #[error("error in conversion of decimal numbers starting in column {column:?}")]
-    DecimalParseFailed { column: usize },
+    #[error("error in conversion of decimal numbers starting in {position}")]
+    DecimalParseFailed { position: Position },
 
     /// 解析失败。
-    #[error("failed to parse from column {column:?}")]
-    ParseFailed { column: usize },
+    #[error("failed to parse from {position}")]
+    ParseFailed { position: Position },
 
     #[error("the value `{}` is not a string", value.to_string())]
     NotAString { value: Value },
@@ -120,9 +145,54 @@ pub enum Error {
     #[error("the value `{}` is not a decimal number", value.to_string())]
     NotADecimal { value: Value },
 
+    #[error("the value `{}` is not a boolean", value.to_string())]
+    NotABoolean { value: Value },
+
+    #[error("the value `{}` is not a range", value.to_string())]
+    NotARange { value: Value },
+
+    /// 不合法的域名。
+    #[error("invalid domain `{pattern}`")]
+    InvalidDomain { pattern: String },
+
+    /// 运算符不接受命名列表。
+    #[error("the `{}` operator does not accept a named list", operator.to_string())]
+    UnsupportedList { operator: Operator },
+
+    /// 匹配时找不到命名列表。
+    #[error("the list `${name}` is not provided")]
+    UnknownList { name: String },
+
+    /// 范围的起点大于终点。
+    #[error("the range `{}` is empty, its start is greater than its end", value.to_string())]
+    InvalidRange { value: Value },
+
     #[error("cannot reference value in empty list")]
     RefValueInEmptyList,
 
+    /// 运算符只接受单个值。
+    #[error("the `{}` operator takes a single value, not a list", operator.to_string())]
+    ExpectedSingleValue { operator: Operator },
+
+    /// 运算符的值列表为空。
+    #[error("the `{}` operator requires at least one value", operator.to_string())]
+    EmptyList { operator: Operator },
+
+    /// 不合法的正则表达式。
+    #[error("invalid regular expression `{pattern}`: {source}")]
+    InvalidRegex {
+        pattern: String,
+        source: regex::Error,
+    },
+
+    /// 构建关键字自动机失败。
+    #[error("failed to build the keyword automaton: {source}")]
+    BuildAutomatonFailed { source: aho_corasick::BuildError },
+
+    /// 附带位置信息的错误。用于编译条件时产生的错误，例如未知的字段或不合法的正则表达式。
+    #[error("{source} (--> {span})")]
+    Located { span: Position, source: Box<Error> },
+
     #[error("{}", source.to_string())]
     #[cfg(feature = "json")]
     Json {
@@ -134,3 +204,63 @@ pub enum Error {
     #[error("falsey result returned early, showing this message may be a bug")]
     FalsyValueHosting,
 }
+
+impl Error {
+    /// 错误在规则中的位置。不包含位置信息的错误返回 `None`。
+    pub fn span(&self) -> Option<Position> {
+        use Error::*;
+
+        match self {
+            Located { span, .. } => Some(*span),
+            ShouldEndHere { position }
+            | ShouldCloseParenthesisHere { position }
+            | MissingField { position }
+            | MissingOperator { position }
+            | MissingValue { position, .. }
+            | MissingQuote { position }
+            | MissingCommentEnd { position }
+            | InvalidEscape { position }
+            | ShouldQuoteHere { position }
+            | ShouldCloseBraceHere { position }
+            | ShouldValueHere { position }
+            | ShouldOpenBraceOrQuote { position }
+            | MissingCondition { position }
+            | IntegerParseFailed { position }
+            | DecimalParseFailed { position }
+            | ParseFailed { position } => Some(*position),
+            _ => None,
+        }
+    }
+
+    /// 去除位置信息的错误。
+    pub fn inner(&self) -> &Error {
+        match self {
+            Error::Located { source, .. } => source.inner(),
+            e => e,
+        }
+    }
+}
+
+fn did_you_mean(suggestion: &Option<String>) -> String {
+    match suggestion {
+        Some(suggestion) => format!(", did you mean `{}`?", suggestion),
+        None => String::new(),
+    }
+}
+
+/// 字段支持的运算符列表的描述。
+pub(crate) fn supported_operators(field: &Field) -> String {
+    let operators = field.operators();
+
+    if operators.is_empty() {
+        String::from("it does not take an operator")
+    } else {
+        let operators = operators
+            .iter()
+            .map(|o| format!("`{}`", o.to_string()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("supported operators are {}", operators)
+    }
+}
@@ -0,0 +1,204 @@
+//! 匹配结果的解释。
+//!
+//! 解释会记录每一个被求值的条件的结果、解析出的字段值和命中的值，用于向用户说明消息被匹配（或未被匹配）的原因。
+
+use super::error::Error;
+use super::matches::{Cont, Context, Expr, Field, ListProvider, Matcher, Scope, Value, Values};
+use super::models::Message;
+use super::operator::{Modifier, Operator};
+use super::result::Result;
+
+/// 匹配结果的解释。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// 是否匹配。
+    pub is_match: bool,
+    /// 匹配成功的条件组序号。
+    pub matched_group: Option<usize>,
+    /// 条件组的求值轨迹。
+    ///
+    /// 顶层表达式为 `or` 时，它的每一个子表达式是一个条件组，否则整个表达式是唯一的条件组。
+    /// 匹配成功后剩余的组不再求值，因此不会出现在列表中。
+    pub groups: Vec<Trace>,
+}
+
+/// 表达式的求值轨迹。
+///
+/// `and` 和 `or` 表达式存在短路，轨迹只包含实际求值过的子表达式。
+#[derive(Debug, Clone, PartialEq)]
+pub enum Trace {
+    /// 单个条件。
+    Cont(ContTrace),
+    /// 取反的表达式。
+    Not(Box<Trace>),
+    /// 具有 `and` 关系的表达式列表。
+    And(Vec<Trace>),
+    /// 具有 `or` 关系的表达式列表。
+    Or(Vec<Trace>),
+}
+
+/// 单个条件的求值轨迹。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContTrace {
+    /// 是否取反。
+    pub is_negative: bool,
+    /// 字段所属的消息。
+    pub scope: Scope,
+    /// 字段。
+    pub field: Field,
+    /// 运算符。
+    pub operator: Option<Operator>,
+    /// 运算符修饰符。
+    pub modifiers: Vec<Modifier>,
+    /// 条件中的值。
+    pub value: Option<Values>,
+    /// 从消息中解析出的字段值，实体和链接字段是以空格连接的全部内容。
+    pub field_value: Option<Value>,
+    /// `any` 和 `all` 运算符命中的值。
+    pub hits: Vec<Value>,
+    /// 条件的结果（已计算取反）。
+    pub is_match: bool,
+}
+
+impl Trace {
+    /// 表达式的结果。
+    pub fn is_match(&self) -> bool {
+        match self {
+            Trace::Cont(trace) => trace.is_match,
+            Trace::Not(trace) => !trace.is_match(),
+            Trace::And(traces) => traces.iter().all(Trace::is_match),
+            Trace::Or(traces) => traces.iter().any(Trace::is_match),
+        }
+    }
+}
+
+impl Matcher {
+    /// 匹配消息并解释匹配结果。
+    ///
+    /// # 例子
+    /// ```
+    /// use matchingram::Matcher;
+    /// use matchingram::explain::Trace;
+    /// use matchingram::models::Message;
+    ///
+    /// let rule = r#"(message.text.len gt 100) or (message.text any {"移动" "联通" "电信"})"#;
+    /// let matcher = Matcher::from_rule(rule)?;
+    /// let message = Message {
+    ///     text: Some(format!("我是移动客服")),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let explanation = matcher.explain(&message)?;
+    /// assert_eq!(explanation.matched_group, Some(1));
+    /// if let Trace::Cont(trace) = &explanation.groups[1] {
+    ///     assert_eq!(trace.hits.len(), 1);
+    /// }
+    ///
+    /// # Ok::<(), matchingram::Error>(())
+    /// ```
+    pub fn explain(&self, message: &Message) -> Result<Explanation> {
+        self.explain_context(&Context::new(message))
+    }
+
+    /// 使用命名列表匹配消息并解释匹配结果。
+    ///
+    /// 规则引用了 `$name` 形式的命名列表时，需要使用这个方法代替 [`Matcher::explain`]。
+    pub fn explain_with_lists(
+        &self,
+        message: &Message,
+        lists: &dyn ListProvider,
+    ) -> Result<Explanation> {
+        self.explain_context(&Context::with_lists(message, lists))
+    }
+
+    fn explain_context(&self, ctx: &Context) -> Result<Explanation> {
+        let exprs = match &self.expr {
+            Expr::Or(exprs) => exprs.iter().collect(),
+            expr => vec![expr],
+        };
+
+        let mut groups = vec![];
+        let mut matched_group = None;
+        for (i, expr) in exprs.into_iter().enumerate() {
+            let trace = expr.explain_context(ctx)?;
+            let is_match = trace.is_match();
+
+            groups.push(trace);
+            if is_match {
+                matched_group = Some(i);
+                break;
+            }
+        }
+
+        Ok(Explanation {
+            is_match: matched_group.is_some(),
+            matched_group,
+            groups,
+        })
+    }
+}
+
+impl Expr {
+    /// 在匹配上下文中求值并记录轨迹。
+    pub fn explain_context(&self, ctx: &Context) -> Result<Trace> {
+        let trace = match self {
+            Expr::Cont(cont) => Trace::Cont(cont.explain_context(ctx)?),
+            Expr::Not(expr) => Trace::Not(Box::new(expr.explain_context(ctx)?)),
+            Expr::And(exprs) => {
+                let mut traces = vec![];
+                for expr in exprs {
+                    let trace = expr.explain_context(ctx)?;
+                    let is_match = trace.is_match();
+
+                    traces.push(trace);
+                    if !is_match {
+                        break;
+                    }
+                }
+
+                Trace::And(traces)
+            }
+            Expr::Or(exprs) => {
+                let mut traces = vec![];
+                for expr in exprs {
+                    let trace = expr.explain_context(ctx)?;
+                    let is_match = trace.is_match();
+
+                    traces.push(trace);
+                    if is_match {
+                        break;
+                    }
+                }
+
+                Trace::Or(traces)
+            }
+        };
+
+        Ok(trace)
+    }
+}
+
+impl Cont {
+    /// 在匹配上下文中求值并记录轨迹。
+    pub fn explain_context(&self, ctx: &Context) -> Result<ContTrace> {
+        Ok(ContTrace {
+            is_negative: self.is_negative,
+            scope: self.scope,
+            field: self.field,
+            operator: self.operator,
+            modifiers: self.modifiers.clone(),
+            value: self.value.clone(),
+            field_value: or_default(self.in_scope(ctx, |ctx| self.field.resolve_context(ctx)))?,
+            hits: or_default(self.in_scope(ctx, |ctx| self.hits(ctx)))?,
+            is_match: self.match_context(ctx)?,
+        })
+    }
+}
+
+// 上级字段不存在时使用默认值。
+fn or_default<T: Default>(result: Result<T>) -> Result<T> {
+    match result {
+        Err(Error::FalsyValueHosting) => Ok(T::default()),
+        r => r,
+    }
+}
@@ -56,6 +56,7 @@
 
 use super::error::Error;
 use super::result::Result;
+use std::fmt;
 
 /// 所有的 Token。
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -80,6 +81,12 @@ pub enum Token {
     Integer,
     /// 小数。
     Decimal,
+    /// 布尔值。
+    Boolean, // true | false
+    /// 范围。
+    Range, // integer..integer | decimal..decimal
+    /// 命名列表的引用。
+    List, // $name
     /// and 关键字。
     And, // and
     /// or 关键字。
@@ -107,12 +114,46 @@ pub struct Lexer<'a> {
     positions: Vec<Position>,
     // 是否处在引号内部。
     is_inside_quotes: bool,
+    // 恢复模式下收集的错误，`None` 表示遇到错误立即返回。
+    errors: Option<Vec<Error>>,
+    // 恢复模式下出错的位置。
+    failures: Vec<usize>,
+    // 每一行的起始位置。
+    line_starts: Vec<usize>,
 }
 
-#[derive(Debug)]
+/// Token 在输入中的位置，以字符为单位，不包括 `end`。
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Position {
     pub begin: usize,
     pub end: usize,
+    /// 起始位置所在的行，从 1 开始。
+    pub line: usize,
+    /// 起始位置所在的列，从 1 开始。
+    pub column: usize,
+}
+
+impl Position {
+    /// 同一行中向后偏移 `n` 个字符的单个字符的位置。
+    pub fn offset(&self, n: usize) -> Self {
+        Position {
+            begin: self.begin + n,
+            end: self.begin + n + 1,
+            line: self.line,
+            column: self.column + n,
+        }
+    }
+}
+
+impl fmt::Display for Position {
+    // 第一行的位置只显示列。
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 1 {
+            write!(f, "column {}", self.column)
+        } else {
+            write!(f, "line {}, column {}", self.line, self.column)
+        }
+    }
 }
 
 impl<'a> Lexer<'a> {
@@ -125,6 +166,17 @@ impl<'a> Lexer<'a> {
             tokens: vec![],
             positions: vec![],
             is_inside_quotes: false,
+            errors: None,
+            failures: vec![],
+            line_starts: std::iter::once(0)
+                .chain(
+                    input
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, c)| **c == '\n')
+                        .map(|(i, _)| i + 1),
+                )
+                .collect(),
         }
     }
 
@@ -140,50 +192,26 @@ impl<'a> Lexer<'a> {
 
     /// 将输入转换为 token 序列。
     pub fn tokenize(&mut self) -> Result<()> {
+        // 规则可直接以条件开头（而不是括号）。
+        let result = self.skip_white_space();
+        self.recover(result)?;
+        if self.cc.is_some() && self.cc != Some(&'(') {
+            let result = self.scan_cont_head();
+            if self.recover(result)? {
+                self.scan();
+            }
+        }
+
         while self.cc.is_some() {
-            self.skip_white_space();
-            if let Some(cc) = self.cc {
-                match cc {
-                    '(' => {
-                        self.push_token(Token::OpenParenthesis)?;
-                        self.scan();
-                        self.skip_white_space();
-                        if !self.scan_field()? {
-                            return Err(Error::MissingField {
-                                column: self.pos + 1,
-                            });
-                        }
-                        self.scan();
-                        self.skip_white_space();
-                        if !self.scan_operator()? {
-                            self.back();
-                        }
-                    }
-                    ')' => self.push_token(Token::CloseParenthesis)?,
-                    '{' => self.push_token(Token::OpenBrace)?,
-                    '}' => self.push_token(Token::CloseBrace)?,
-                    '"' => {
-                        self.is_inside_quotes = !self.is_inside_quotes;
-                        self.push_token(Token::Quote)?;
-                        if self.is_inside_quotes {
-                            self.scan();
-                            if !self.scan_letter()? {
-                                return Err(Error::MissingQuote {
-                                    column: self.pos + 1,
-                                });
-                            }
-                        }
-                    }
-                    _ => {
-                        if !self.scan_keywords()? && !self.scan_number()? {
-                            return Err(Error::ParseFailed {
-                                column: self.pos + 1,
-                            });
-                        }
-                    }
+            let result = self.skip_white_space();
+            if !self.recover(result)? {
+                continue;
+            }
+            if let Some(&cc) = self.cc {
+                let result = self.tokenize_next(cc);
+                if self.recover(result)? {
+                    self.scan();
                 }
-
-                self.scan();
             }
         }
 
@@ -192,6 +220,103 @@ impl<'a> Lexer<'a> {
         Ok(())
     }
 
+    /// 以恢复模式将输入转换为 token 序列，返回全部的词法错误。
+    ///
+    /// 出错的部分会被跳过（未闭合的字符串跳过到行尾），分析会继续进行。
+    pub fn tokenize_recovering(&mut self) -> Vec<Error> {
+        self.errors = Some(vec![]);
+        let result = self.tokenize();
+        let mut errors = self.errors.take().unwrap_or_default();
+
+        if let Err(e) = result {
+            errors.push(e);
+        }
+
+        errors
+    }
+
+    /// 恢复模式下出错的位置。
+    pub(crate) fn failures(&self) -> &[usize] {
+        &self.failures
+    }
+
+    // 分析以当前字符开始的 token。
+    fn tokenize_next(&mut self, cc: char) -> Result<()> {
+        match cc {
+            '(' => {
+                self.push_token(Token::OpenParenthesis)?;
+                self.scan();
+                self.scan_cont_head()?;
+            }
+            ')' => self.push_token(Token::CloseParenthesis)?,
+            '{' => self.push_token(Token::OpenBrace)?,
+            '}' => self.push_token(Token::CloseBrace)?,
+            '"' => {
+                self.is_inside_quotes = !self.is_inside_quotes;
+                self.push_token(Token::Quote)?;
+                if self.is_inside_quotes {
+                    self.scan();
+                    if !self.scan_letter()? {
+                        // 定位到左引号。
+                        return Err(Error::MissingQuote {
+                            position: self.at(self.pos - 1),
+                        });
+                    }
+                }
+            }
+            '$' => {
+                if !self.scan_list()? {
+                    return Err(Error::ParseFailed {
+                        position: self.at(self.pos),
+                    });
+                }
+            }
+            _ => {
+                if !self.scan_keywords()? && !self.scan_number()? && !self.scan_boolean()? {
+                    return Err(Error::ParseFailed {
+                        position: self.at(self.pos),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // 在恢复模式下记录错误并跳过出错的部分，否则直接返回错误。成功时返回 `true`。
+    fn recover(&mut self, result: Result<()>) -> Result<bool> {
+        let error = match result {
+            Ok(()) => return Ok(true),
+            Err(e) => e,
+        };
+        let errors = match &mut self.errors {
+            Some(errors) => errors,
+            None => return Err(error),
+        };
+
+        let is_quote = matches!(error, Error::MissingQuote { .. });
+        if let Some(span) = error.span() {
+            self.failures.push(span.begin);
+        }
+        errors.push(error);
+
+        // 跳过到下一个空白或括号，未闭合的字符串则跳过到行尾。
+        self.is_inside_quotes = false;
+        while let Some(cc) = self.cc {
+            let is_end = if is_quote {
+                *cc == '\n'
+            } else {
+                self.cc.is_white_space() || ['(', ')', '{', '}'].contains(cc)
+            };
+            if is_end {
+                break;
+            }
+            self.scan();
+        }
+
+        Ok(false)
+    }
+
     // 扫描关键字。
     fn scan_keywords(&mut self) -> Result<bool> {
         if let Some(cc) = self.cc {
@@ -199,24 +324,23 @@ impl<'a> Lexer<'a> {
                 'a' => {
                     if self.tokenize_and()? {
                         self.scan();
-                        self.skip_white_space();
-                        if !self.scan_field()? {
-                            return Err(Error::MissingField {
-                                column: self.pos + 1,
-                            });
-                        }
+                        self.scan_cont_head()?;
+
+                        Ok(true)
+                    } else {
+                        Ok(false)
+                    }
+                }
+                'o' => {
+                    if self.tokenize_or()? {
                         self.scan();
-                        self.skip_white_space();
-                        if !self.scan_operator()? {
-                            self.back();
-                        }
+                        self.scan_cont_head()?;
 
                         Ok(true)
                     } else {
                         Ok(false)
                     }
                 }
-                'o' => self.tokenize_or(),
                 'n' => self.tokenize_not(),
                 _ => Ok(false),
             }
@@ -225,73 +349,153 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    // 扫描数字。
-    // 包括整数、小数。
-    fn scan_number(&mut self) -> Result<bool> {
+    // 扫描条件的头部，即可选的 `not` 关键字、字段和运算符。
+    //
+    // 如果条件是一个嵌套的括号表达式，则回退到括号之前的位置，交由主循环处理。
+    fn scan_cont_head(&mut self) -> Result<()> {
+        self.skip_white_space()?;
+        while self.cc == Some(&'n') && self.tokenize_not()? {
+            self.scan();
+            self.skip_white_space()?;
+        }
+
+        if self.cc == Some(&'(') {
+            self.back();
+            return Ok(());
+        }
+
+        if !self.scan_field()? {
+            return Err(Error::MissingField {
+                position: self.at(self.pos),
+            });
+        }
+        self.scan();
+        self.skip_white_space()?;
+        if !self.scan_operator()? {
+            self.back();
+        }
+
+        Ok(())
+    }
+
+    // 扫描布尔值（`true` 或 `false`）。
+    fn scan_boolean(&mut self) -> Result<bool> {
         let begin_pos = self.pos;
-        let signed = self.at_char(begin_pos) == Some(&'-');
-        let mut end_pos = if signed { begin_pos + 1 } else { begin_pos };
 
-        while self.at_char(end_pos).is_integer() {
+        for literal in &["true", "false"] {
+            let end_pos = begin_pos + literal.len();
+            let is_literal = literal
+                .chars()
+                .enumerate()
+                .all(|(i, c)| self.at_char(begin_pos + i) == Some(&c));
+            let end_char = self.at_char(end_pos);
+            // 检查是否合法结束
+            let is_end =
+                end_char.is_white_space() || matches!(end_char, Some(&'}') | Some(&')') | None);
+
+            if is_literal && is_end {
+                self.scan_at(end_pos - 1);
+                self.push_token_position(Token::Boolean, self.position(begin_pos, end_pos));
+
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    // 扫描命名列表的引用（`$` 加名称）。
+    // 名称由字母、数字和下划线构成。
+    fn scan_list(&mut self) -> Result<bool> {
+        let begin_pos = self.pos;
+        let mut end_pos = begin_pos + 1;
+
+        while matches!(self.at_char(end_pos), Some(c) if c.is_ascii_alphanumeric() || *c == '_') {
             end_pos += 1;
         }
 
         let end_char = self.at_char(end_pos);
-        let is_integer = end_pos > if signed {begin_pos + 1} else {begin_pos}
-            // 检查是否合法结束
-            && (end_char.is_white_space() || match end_char {
-                Some(&'}') => true,
-                Some(&')') => true,
-                _ => false,
-            });
+        // 检查是否合法结束
+        let is_end =
+            end_char.is_white_space() || matches!(end_char, Some(&'}') | Some(&')') | None);
 
-        if is_integer {
+        if end_pos > begin_pos + 1 && is_end {
             self.scan_at(end_pos - 1);
-            self.push_token_position(
-                Token::Integer,
-                Position {
-                    begin: begin_pos,
-                    end: end_pos,
-                },
-            );
+            self.push_token_position(Token::List, self.position(begin_pos, end_pos));
 
             Ok(true)
-        } else if end_char == Some(&'.') {
-            // 可能是小数
-            let mut con_pos = end_pos + 1; // 继续扫描的位置
+        } else {
+            Ok(false)
+        }
+    }
 
-            while self.at_char(con_pos).is_integer() {
-                con_pos += 1;
-            }
+    // 扫描数字。
+    // 包括整数、小数和由两个数字构成的范围（如 `1..5`）。
+    fn scan_number(&mut self) -> Result<bool> {
+        let begin_pos = self.pos;
 
-            let end_char = self.at_char(con_pos);
-            let is_decimal = con_pos > end_pos + 1
-            // 检查是否合法结束
-            && (end_char.is_white_space() || match end_char {
-                Some(&'}') => true,
-                Some(&')') => true,
-                _ => false,
-            });
+        let (end_pos, is_decimal) = match self.number_end(begin_pos) {
+            Some(end) => end,
+            None => return Ok(false),
+        };
 
-            if is_decimal {
-                self.scan_at(con_pos - 1);
-                self.push_token_position(
-                    Token::Decimal,
-                    Position {
-                        begin: begin_pos,
-                        end: con_pos,
-                    },
-                );
-
-                Ok(true)
+        // 可能是范围
+        let range_end =
+            if self.at_char(end_pos) == Some(&'.') && self.at_char(end_pos + 1) == Some(&'.') {
+                self.number_end(end_pos + 2).map(|(end, _)| end)
             } else {
-                Ok(false)
-            }
+                None
+            };
+
+        let (token, end_pos) = match range_end {
+            Some(range_end) => (Token::Range, range_end),
+            None if is_decimal => (Token::Decimal, end_pos),
+            None => (Token::Integer, end_pos),
+        };
+
+        let end_char = self.at_char(end_pos);
+        // 检查是否合法结束
+        let is_end =
+            end_char.is_white_space() || matches!(end_char, Some(&'}') | Some(&')') | None);
+
+        if is_end {
+            self.scan_at(end_pos - 1);
+            self.push_token_position(token, self.position(begin_pos, end_pos));
+
+            Ok(true)
         } else {
             Ok(false)
         }
     }
 
+    // 查找从指定位置开始的数字的结束位置，并返回是否为小数。
+    // 不检查数字之后的字符。
+    fn number_end(&self, begin_pos: usize) -> Option<(usize, bool)> {
+        let mut end_pos = begin_pos;
+        if self.at_char(end_pos) == Some(&'-') {
+            end_pos += 1;
+        }
+
+        let digits_begin = end_pos;
+        while self.at_char(end_pos).is_integer() {
+            end_pos += 1;
+        }
+        if end_pos == digits_begin {
+            return None;
+        }
+
+        if self.at_char(end_pos) == Some(&'.') && self.at_char(end_pos + 1).is_integer() {
+            end_pos += 1;
+            while self.at_char(end_pos).is_integer() {
+                end_pos += 1;
+            }
+
+            Some((end_pos, true))
+        } else {
+            Some((end_pos, false))
+        }
+    }
+
     // 扫描字面值（字符串）
     fn scan_letter(&mut self) -> Result<bool> {
         // 如果不在引号内部，则不扫描。
@@ -304,22 +508,20 @@ impl<'a> Lexer<'a> {
         let mut separator = self.at_char(end_pos);
         // 如果没有被双引号或换行截断，继续扫描
         while separator.is_some() && separator != Some(&'"') && separator != Some(&'\n') {
+            // 跳过转义序列的下一个字符，被转义的双引号不会截断字符串。转义序列由语法分析器处理。
+            if separator == Some(&'\\') && self.at_char(end_pos + 1).is_some() {
+                end_pos += 1;
+            }
             end_pos += 1;
             separator = self.at_char(end_pos);
         }
 
-        // 合法结束检查条件：以双引号截断（而不是换行）
-        let is_letter = end_pos > begin_pos && separator == Some(&'"');
+        // 合法结束检查条件：以双引号截断（而不是换行）。空字符串 `""` 同样合法。
+        let is_letter = separator == Some(&'"');
 
         if is_letter {
             self.scan_at(end_pos - 1);
-            self.push_token_position(
-                Token::Letter,
-                Position {
-                    begin: begin_pos,
-                    end: end_pos,
-                },
-            );
+            self.push_token_position(Token::Letter, self.position(begin_pos, end_pos));
 
             Ok(true)
         } else {
@@ -332,42 +534,15 @@ impl<'a> Lexer<'a> {
         use Token::*;
 
         let position = match &token {
-            OpenParenthesis => Position {
-                begin: self.pos,
-                end: self.pos + 1,
-            },
-            CloseParenthesis => Position {
-                begin: self.pos,
-                end: self.pos + 1,
-            },
-            OpenBrace => Position {
-                begin: self.pos,
-                end: self.pos + 1,
-            },
-            CloseBrace => Position {
-                begin: self.pos,
-                end: self.pos + 1,
-            },
-            Quote => Position {
-                begin: self.pos,
-                end: self.pos + 1,
-            },
-            And => Position {
-                begin: self.pos - 2,
-                end: self.pos + 1,
-            },
-            Or => Position {
-                begin: self.pos - 1,
-                end: self.pos + 1,
-            },
-            Not => Position {
-                begin: self.pos - 2,
-                end: self.pos + 1,
-            },
-            EOF => Position {
-                begin: self.pos,
-                end: self.pos,
-            },
+            OpenParenthesis => self.position(self.pos, self.pos + 1),
+            CloseParenthesis => self.position(self.pos, self.pos + 1),
+            OpenBrace => self.position(self.pos, self.pos + 1),
+            CloseBrace => self.position(self.pos, self.pos + 1),
+            Quote => self.position(self.pos, self.pos + 1),
+            And => self.position(self.pos - 2, self.pos + 1),
+            Or => self.position(self.pos - 1, self.pos + 1),
+            Not => self.position(self.pos - 2, self.pos + 1),
+            EOF => self.position(self.pos, self.pos),
             _ => return Err(Error::InferPositionFailed { token: token }),
         };
         self.push_token_position(token, position);
@@ -375,6 +550,23 @@ impl<'a> Lexer<'a> {
         Ok(())
     }
 
+    // 由起止位置创建位置信息，同时计算所在的行和列。
+    fn position(&self, begin: usize, end: usize) -> Position {
+        let line = self.line_starts.partition_point(|start| *start <= begin);
+
+        Position {
+            begin,
+            end,
+            line,
+            column: begin - self.line_starts[line - 1] + 1,
+        }
+    }
+
+    // 单个字符的位置，用于错误定位。
+    fn at(&self, pos: usize) -> Position {
+        self.position(pos, pos + 1)
+    }
+
     fn push_token_position(&mut self, token: Token, position: Position) {
         self.positions.push(position);
         self.tokens.push(token);
@@ -383,7 +575,7 @@ impl<'a> Lexer<'a> {
     fn tokenize_not(&mut self) -> Result<bool> {
         if self.at_char(self.pos + 1) == Some(&'o')
             && self.at_char(self.pos + 2) == Some(&'t')
-            && self.at_char(self.pos + 3).is_white_space()
+            && self.is_keyword_end(self.pos + 3)
         {
             self.scan_at(self.pos + 2);
             self.push_token(Token::Not)?;
@@ -395,15 +587,11 @@ impl<'a> Lexer<'a> {
     }
 
     fn scan_field(&mut self) -> Result<bool> {
-        if self.cc == Some(&'n') && self.tokenize_not()? {
-            self.scan();
-            self.skip_white_space();
-        }
         let begin_pos = self.pos;
         let mut cur_pos = begin_pos;
         let mut end_char = self.at_char(cur_pos);
 
-        while end_char != Some(&')') && !end_char.is_white_space() {
+        while end_char.is_some() && end_char != Some(&')') && !end_char.is_white_space() {
             cur_pos += 1;
             end_char = self.at_char(cur_pos);
         }
@@ -411,13 +599,7 @@ impl<'a> Lexer<'a> {
         let is_field = cur_pos > begin_pos;
 
         if is_field {
-            self.push_token_position(
-                Token::Field,
-                Position {
-                    begin: begin_pos,
-                    end: cur_pos,
-                },
-            );
+            self.push_token_position(Token::Field, self.position(begin_pos, cur_pos));
             self.scan_at(cur_pos - 1);
         }
 
@@ -432,8 +614,11 @@ impl<'a> Lexer<'a> {
         if self.cc == Some(&'a') && self.is_and_keywords() {
             return Ok(false);
         }
+        if self.cc == Some(&'o') && self.is_or_keywords() {
+            return Ok(false);
+        }
 
-        while end_char != Some(&')') && !end_char.is_white_space() {
+        while end_char.is_some() && end_char != Some(&')') && !end_char.is_white_space() {
             cur_pos += 1;
             end_char = self.at_char(cur_pos);
         }
@@ -441,13 +626,7 @@ impl<'a> Lexer<'a> {
         let is_operator = cur_pos > begin_pos;
 
         if is_operator {
-            self.push_token_position(
-                Token::Operator,
-                Position {
-                    begin: begin_pos,
-                    end: cur_pos,
-                },
-            );
+            self.push_token_position(Token::Operator, self.position(begin_pos, cur_pos));
             self.scan_at(cur_pos - 1);
         }
 
@@ -455,7 +634,7 @@ impl<'a> Lexer<'a> {
     }
 
     fn tokenize_or(&mut self) -> Result<bool> {
-        if self.at_char(self.pos + 1) == Some(&'r') && self.at_char(self.pos + 2).is_white_space() {
+        if self.is_or_keywords() {
             self.scan_at(self.pos + 1);
             self.push_token(Token::Or)?;
 
@@ -480,7 +659,19 @@ impl<'a> Lexer<'a> {
     fn is_and_keywords(&self) -> bool {
         self.at_char(self.pos + 1) == Some(&'n')
             && self.at_char(self.pos + 2) == Some(&'d')
-            && self.at_char(self.pos + 3).is_white_space()
+            && self.is_keyword_end(self.pos + 3)
+    }
+
+    // 当前位置是否是 `or` 关键字。
+    fn is_or_keywords(&self) -> bool {
+        self.at_char(self.pos + 1) == Some(&'r') && self.is_keyword_end(self.pos + 2)
+    }
+
+    // 指定位置是否是关键字的合法结束（空白或开启的小括号）。
+    fn is_keyword_end(&self, pos: usize) -> bool {
+        let end_char = self.at_char(pos);
+
+        end_char.is_white_space() || end_char == Some(&'(')
     }
 
     // 扫描下一个字符并自增指针位置。
@@ -512,20 +703,52 @@ impl<'a> Lexer<'a> {
         self.cc
     }
 
-    // 跳过空白字符。返回跳过的字符数量。
-    fn skip_white_space(&mut self) -> usize {
-        let mut begin_pos = self.pos;
-        while self.cc.is_white_space() {
-            self.scan();
-            begin_pos += 1;
+    // 跳过空白字符和注释。
+    //
+    // 支持 `#` 或 `//` 开头的行注释，以及 `/* */` 包裹的块注释。
+    fn skip_white_space(&mut self) -> Result<()> {
+        loop {
+            while self.cc.is_white_space() {
+                self.scan();
+            }
+
+            let next = self.at_char(self.pos + 1);
+            match self.cc {
+                Some(&'#') => self.skip_line(),
+                Some(&'/') if next == Some(&'/') => self.skip_line(),
+                Some(&'/') if next == Some(&'*') => {
+                    let begin_pos = self.pos;
+                    let end = (begin_pos + 2..self.input.len()).find(|&i| {
+                        self.at_char(i) == Some(&'*') && self.at_char(i + 1) == Some(&'/')
+                    });
+
+                    match end {
+                        Some(end) => self.scan_at(end + 2),
+                        None => {
+                            // 未闭合的块注释延续到输入结束。
+                            self.scan_at(self.input.len());
+
+                            return Err(Error::MissingCommentEnd {
+                                position: self.at(begin_pos),
+                            });
+                        }
+                    };
+                }
+                _ => return Ok(()),
+            }
         }
+    }
 
-        return self.pos - begin_pos;
+    // 跳过到行尾（不包括换行符）。
+    fn skip_line(&mut self) {
+        while self.cc.is_some() && self.cc != Some(&'\n') {
+            self.scan();
+        }
     }
 
     /// 分析是否结束。
     pub fn is_end(&self) -> bool {
-        self.pos > self.input.len() - 1
+        self.pos >= self.input.len()
     }
 
     /// 生成 token 与数据（引用）的映射序列。
@@ -17,23 +17,37 @@
 //!
 //! # 例外情况：
 //! 1. 不具有运算符和值的条件直接使用字段构成，前置 `not` 亦可取反。例如：`(cf.client.bot)`。
+//! 1. 条件组可以任意嵌套，`not` 也可以作用于整个括号表达式。运算的优先级由高到低依次为 `not`、`and`、`or`。例如：
+//!    `(message.from.is_bot and not (message.text any {"a" "b"} or message.text.len gt 5))`。
 
 #![feature(min_specialization)]
 
+pub mod diagnostic;
 pub mod error;
+pub mod explain;
 pub mod falsey;
 pub mod lexer;
 pub mod matches;
 pub mod models;
 pub mod operator;
 pub mod parser;
+pub mod printer;
 pub mod result;
+pub mod rule_set;
+mod suggestion;
+#[cfg(feature = "serialize")]
+pub mod serialization;
 pub mod truthy;
+mod url;
 
+#[doc(inline)]
+pub use diagnostic::Diagnostic;
 #[doc(inline)]
 pub use error::Error;
 #[doc(inline)]
-pub use matches::Matcher;
+pub use matches::{CompiledList, ListProvider, Matcher};
+#[doc(inline)]
+pub use rule_set::RuleSet;
 use models::Message;
 use result::Result;
 
@@ -59,21 +73,21 @@ use result::Result;
 /// # Ok::<(), matchingram::Error>(())
 /// ```
 pub fn rule_match<S: Into<String>>(rule: S, message: &Message) -> Result<bool> {
-    let mut matcher = compile_rule(rule)?;
+    let matcher = compile_rule(rule)?;
 
-    matcher_match(&mut matcher, message)
+    matcher_match(&matcher, message)
 }
 
 /// 使用匹配器对象匹配消息。
 ///
 /// 通过 [`compile_rule`](fn.compile_rule.html) 函数编译规则得到匹配器。
-pub fn matcher_match(matcher: &mut Matcher, message: &Message) -> Result<bool> {
+pub fn matcher_match(matcher: &Matcher, message: &Message) -> Result<bool> {
     matcher.match_message(message)
 }
 
 /// 使用匹配器对象匹配消息的 JSON 数据。
 #[cfg(feature = "json")]
-pub fn matcher_match_json<S: Into<String>>(matcher: &mut Matcher, json_data: S) -> Result<bool> {
+pub fn matcher_match_json<S: Into<String>>(matcher: &Matcher, json_data: S) -> Result<bool> {
     let message: Message = serde_json::from_str(&json_data.into())?;
 
     matcher.match_message(&message)
@@ -95,9 +109,9 @@ pub fn matcher_match_json<S: Into<String>>(matcher: &mut Matcher, json_data: S)
 /// ```
 #[cfg(feature = "json")]
 pub fn rule_match_json<S1: Into<String>, S2: Into<String>>(rule: S1, json: S2) -> Result<bool> {
-    let mut matcher = compile_rule(rule)?;
+    let matcher = compile_rule(rule)?;
 
-    matcher_match_json(&mut matcher, json)
+    matcher_match_json(&matcher, json)
 }
 
 /// 将字符串表达式规则编译为匹配器对象。
@@ -106,3 +120,12 @@ pub fn rule_match_json<S1: Into<String>, S2: Into<String>>(rule: S1, json: S2) -
 pub fn compile_rule<S: Into<String>>(rule: S) -> Result<Matcher> {
     Matcher::from_rule(rule)
 }
+
+/// 将字符串表达式规则编译为匹配器对象，失败时返回规则中的全部错误。
+///
+/// 详情请参照 [`Matcher::from_rule_recovering`](struct.Matcher.html#method.from_rule_recovering) 函数。
+pub fn compile_rule_recovering<S: Into<String>>(
+    rule: S,
+) -> std::result::Result<Matcher, Vec<Error>> {
+    Matcher::from_rule_recovering(rule)
+}
@@ -1,20 +1,39 @@
 //! 消息匹配实现。
 
+use aho_corasick::AhoCorasick;
 use lazy_static::lazy_static;
 use maplit::hashmap;
-use std::collections::HashMap;
+use regex::Regex;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard};
+use strum::IntoEnumIterator;
 use strum_macros::{EnumString, ToString};
 
 use super::error::Error;
 use super::falsey::UnwrapOrFalseyHosting;
 use super::models::Message;
-use super::operator::{prelude::*, Operator};
+use super::operator::{normalize, prelude::*, Modifier, Operator};
 use super::result::Result;
+use super::suggestion::did_you_mean;
 use super::truthy::IsTruthy;
+use super::url::{find_urls, Url};
 
 pub type ContGroups = Vec<Vec<Cont>>;
 pub type Values = Vec<Value>;
+type NormalizedTexts = HashMap<(Field, Vec<Modifier>), Rc<Option<String>>>;
+type Contents = HashMap<Field, Rc<Vec<String>>>;
+type Items = HashMap<Field, Rc<Vec<Option<Value>>>>;
+type CompiledConts = HashMap<(Field, Operator, Vec<Modifier>), Arc<Cont>>;
+
+// 关键字数量达到此值时才编译自动机，较少的关键字直接逐个查找更快。
+const KEYWORDS_AUTOMATON_THRESHOLD: usize = 16;
+// 整数数量达到此值时才构建哈希集合，较少的整数直接逐个比较更快。
+const INTEGER_SET_THRESHOLD: usize = 16;
 
 lazy_static! {
     static ref FIELD_OPERATORS: HashMap<&'static Field, &'static [Operator]> = {
@@ -22,65 +41,107 @@ lazy_static! {
         use Operator::*;
 
         hashmap! {
-            &MessageFromId                  => &[Eq, Gt, Ge, Le][..],
-            &MessageFromIsBot               => &[][..],
-            &MessageFromFirstName           => &[Eq, In, Any, All, Hd][..],
-            &MessageFromLastName            => &[Eq, In, Any, All, Hd][..],
-            &MessageFromFullName            => &[Eq, In, Any, All, Hd][..],
-            &MessageFromLanguageCode        => &[Eq, In, Hd][..],
-            &MessageForwardFromChat         => &[][..],
-            &MessageForwardFromChatId       => &[Eq, Gt, Ge, Le][..],
-            &MessageForwardFromChatType     => &[Eq, In][..],
-            &MessageForwardFromChatTitle    => &[Eq, Any, All, Hd][..],
-            &MessageReplyToMessage          => &[][..],
-            &MessageText                    => &[Eq, In, Any, All][..],
-            &MessageTextLen                 => &[Eq, Gt, Ge, Le][..],
-            &MessageAnimation               => &[][..],
-            &MessageAnimationDuration       => &[Eq, Gt, Ge, Le][..],
-            &MessageAnimationFileName       => &[Eq, Any, All, Hd][..],
-            &MessageAnimationMimeType       => &[Eq, In, Hd][..],
-            &MessageAnimationFileSize       => &[Eq, Gt, Ge, Le][..],
-            &MessageAudio                   => &[][..],
-            &MessageAudioDuration           => &[Eq, Gt, Ge, Le][..],
-            &MessageAudioPerformer          => &[Eq, All, Any, Hd][..],
-            &MessageAudioMimeType           => &[Eq, In, Hd][..],
-            &MessageAudioFileSize           => &[Eq, Gt, Ge, Le][..],
-            &MessageDocument                => &[][..],
-            &MessageDocumentFileName        => &[Eq, All, Any, Hd][..],
-            &MessageDocumentMimeType        => &[Eq, In, Hd][..],
-            &MessageDocumentFileSize        => &[Eq, Gt, Ge, Le][..],
-            &MessagePhoto                   => &[][..],
-            &MessageSticker                 => &[][..],
-            &MessageStickerIsAnimated       => &[][..],
-            &MessageStickerEmoji            => &[Eq, In][..],
-            &MessageStickerSetName          => &[Eq, All, Any, Hd][..],
-            &MessageVideo                   => &[][..],
-            &MessageVideoDuration           => &[Eq, Gt, Ge, Le][..],
-            &MessageVideoMimeType           => &[Eq, In, Hd][..],
-            &MessageVideoFileSize           => &[Eq, Gt, Ge, Le][..],
-            &MessageVoice                   => &[][..],
-            &MessageVoiceDuration           => &[Eq, Gt, Ge, Le][..],
-            &MessageVoiceMimeType           => &[Eq, In, Hd][..],
-            &MessageVoiceFileSize           => &[Eq, Gt, Ge, Le][..],
-            &MessageCaption                 => &[Eq, All, Any, Hd][..],
-            &MessageCaptionLen              => &[Eq, Gt, Ge, Le][..],
-            &MessageDice                    => &[][..],
-            &MessageDiceEmoji               => &[Eq, In][..],
-            &MessagePoll                    => &[][..],
-            &MessagePollType                => &[Eq, In][..],
-            &MessageVenue                   => &[][..],
-            &MessageVenueTitle              => &[Eq, All, Any, Hd][..],
-            &MessageVenueAddress            => &[Eq, All, Any, Hd][..],
-            &MessageLocation                => &[][..],
-            &MessageLocationLongitude       => &[Eq, Gt, Ge, Le][..],
-            &MessageLocationLatitude        => &[Eq, Gt, Ge, Le][..],
-            &MessageNewChatMembers          => &[][..],
-            &MessageLeftChatMember          => &[][..],
-            &MessageNewChatTitle            => &[][..],
-            &MessageNewChatPhoto            => &[][..],
-            &MessagePinnedMessage           => &[][..],
-            &MessageIsServiceMessage        => &[][..],
-            &MessageIsCommand               => &[][..],
+            &MessageFromId                    => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageFromIsBot                 => &[Eq][..],
+            &MessageFromFirstName             => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageFromLastName              => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageFromFullName              => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageFromLanguageCode          => &[Eq, In, Hd, Td][..],
+            &MessageSenderChat                => &[Eq][..],
+            &MessageSenderChatId              => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageSenderChatType            => &[Eq, In, Td][..],
+            &MessageSenderChatTitle           => &[Eq, Any, All, Hd, Td][..],
+            &MessageForwardFrom               => &[Eq][..],
+            &MessageForwardFromId             => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageForwardFromIsBot          => &[Eq][..],
+            &MessageForwardFromFirstName      => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageForwardFromLastName       => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageForwardFromFullName       => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageForwardFromUsername       => &[Eq, In, Hd, Td, Re, ReAny][..],
+            &MessageForwardFromChat           => &[Eq][..],
+            &MessageForwardFromChatId         => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageForwardFromChatType       => &[Eq, In, Td][..],
+            &MessageForwardFromChatTitle      => &[Eq, Any, All, Hd, Td][..],
+            &MessageForwardSenderName         => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageForwardDate               => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageReplyToMessage            => &[Eq][..],
+            &MessageViaBot                    => &[Eq][..],
+            &MessageViaBotId                  => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageViaBotFirstName           => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageViaBotUsername            => &[Eq, In, Hd, Td, Re, ReAny][..],
+            &MessageText                      => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageTextLen                   => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageAnimation                 => &[Eq][..],
+            &MessageAnimationDuration         => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageAnimationFileName         => &[Eq, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageAnimationMimeType         => &[Eq, In, Hd, Td][..],
+            &MessageAnimationFileSize         => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageAudio                     => &[Eq][..],
+            &MessageAudioDuration             => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageAudioPerformer            => &[Eq, All, Any, Hd, Td][..],
+            &MessageAudioMimeType             => &[Eq, In, Hd, Td][..],
+            &MessageAudioFileSize             => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageDocument                  => &[Eq][..],
+            &MessageDocumentFileName          => &[Eq, All, Any, Hd, Td, Re, ReAny][..],
+            &MessageDocumentMimeType          => &[Eq, In, Hd, Td][..],
+            &MessageDocumentFileSize          => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessagePhoto                     => &[Eq][..],
+            &MessagePhotoCount                => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessagePhotoAnyWidth             => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessagePhotoAllWidth             => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessagePhotoAnyHeight            => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessagePhotoAllHeight            => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessagePhotoAnyFileSize          => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessagePhotoAllFileSize          => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageSticker                   => &[Eq][..],
+            &MessageStickerIsAnimated         => &[Eq][..],
+            &MessageStickerEmoji              => &[Eq, In, Td][..],
+            &MessageStickerSetName            => &[Eq, All, Any, Hd, Td][..],
+            &MessageVideo                     => &[Eq][..],
+            &MessageVideoDuration             => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageVideoMimeType             => &[Eq, In, Hd, Td][..],
+            &MessageVideoFileSize             => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageVoice                     => &[Eq][..],
+            &MessageVoiceDuration             => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageVoiceMimeType             => &[Eq, In, Hd, Td][..],
+            &MessageVoiceFileSize             => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageCaption                   => &[Eq, All, Any, Hd, Td, Re, ReAny][..],
+            &MessageCaptionLen                => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageEntitiesUrl               => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageEntitiesMention           => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageEntitiesHashtag           => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageEntitiesTextLinkUrl       => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageEntitiesCount             => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageEntitiesAnyType           => &[Eq, In][..],
+            &MessageEntitiesAllType           => &[Eq, In][..],
+            &MessageUrlsHost                  => &[Eq, In, Any, All, Hd, Td, Re, ReAny, DomainIn][..],
+            &MessageUrlsDomain                => &[Eq, In, Td, DomainIn][..],
+            &MessageUrlsPath                  => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageDice                      => &[Eq][..],
+            &MessageDiceEmoji                 => &[Eq, In, Td][..],
+            &MessagePoll                      => &[Eq][..],
+            &MessagePollType                  => &[Eq, In, Td][..],
+            &MessageVenue                     => &[Eq][..],
+            &MessageVenueTitle                => &[Eq, All, Any, Hd, Td][..],
+            &MessageVenueAddress              => &[Eq, All, Any, Hd, Td][..],
+            &MessageLocation                  => &[Eq][..],
+            &MessageLocationLongitude         => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageLocationLatitude          => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageNewChatMembers            => &[Eq][..],
+            &MessageNewChatMembersCount       => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageNewChatMembersAnyId       => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageNewChatMembersAllId       => &[Eq, Gt, Lt, Ge, Le, In, Between][..],
+            &MessageNewChatMembersAnyIsBot    => &[Eq][..],
+            &MessageNewChatMembersAllIsBot    => &[Eq][..],
+            &MessageNewChatMembersAnyFullName => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageNewChatMembersAllFullName => &[Eq, In, Any, All, Hd, Td, Re, ReAny][..],
+            &MessageLeftChatMember            => &[Eq][..],
+            &MessageNewChatTitle              => &[Eq][..],
+            &MessageNewChatPhoto              => &[Eq][..],
+            &MessagePinnedMessage             => &[Eq][..],
+            &MessageIsServiceMessage          => &[Eq][..],
+            &MessageIsCommand                 => &[Eq][..],
+            &MessageIsForwarded               => &[Eq][..],
         }
     };
 }
@@ -88,7 +149,7 @@ lazy_static! {
 /// 匹配器。一般作为表达式的编译目标。
 ///
 /// 匹配器可表达与字符串规则完全对应的结构化的条件关系。
-/// 每个匹配器对象都具备一个“条件组”序列。
+/// 每个匹配器对象都具备一个条件表达式（树）。
 /// ```
 /// use matchingram::models::Message;
 /// use matchingram::matches::*;
@@ -97,27 +158,27 @@ lazy_static! {
 /// // 手动创建一个匹配器对象：
 /// let groups = vec![
 ///     vec![
-///         Cont {
-///             is_negative: false,
-///             field: Field::MessageText,
-///             operator: Some(Operator::Any),
-///             value: Some(vec![Value::from_str("柬埔寨"), Value::from_str("东南亚")]),
-///         },
-///         Cont {
-///             is_negative: false,
-///             field: Field::MessageText,
-///             operator: Some(Operator::Any),
-///             value: Some(vec![Value::from_str("菠菜"), Value::from_str("博彩")]),
-///         },
+///         Cont::build(
+///             false,
+///             Field::MessageText,
+///             Operator::Any,
+///             vec![Value::from_str("柬埔寨"), Value::from_str("东南亚")],
+///         )?,
+///         Cont::build(
+///             false,
+///             Field::MessageText,
+///             Operator::Any,
+///             vec![Value::from_str("菠菜"), Value::from_str("博彩")],
+///         )?,
 ///     ],
-///     vec![Cont {
-///         is_negative: false,
-///         field: Field::MessageText,
-///         operator: Some(Operator::All),
-///         value: Some(vec![Value::from_str("承接"), Value::from_str("广告")]),
-///     }],
+///     vec![Cont::build(
+///         false,
+///         Field::MessageText,
+///         Operator::All,
+///         vec![Value::from_str("承接"), Value::from_str("广告")],
+///     )?],
 /// ];
-/// let mut matcher = Matcher::new(groups);
+/// let matcher = Matcher::new(groups);
 /// // 两条典型的东南亚博彩招人消息
 /// let message_text1 = format!("柬埔寨菠菜需要的来");
 /// let message_text2 = format!("东南亚博彩招聘");
@@ -146,13 +207,14 @@ lazy_static! {
 /// ```text
 /// (message.text any {"柬埔寨" "东南亚"} and message.text any {"菠菜" "博彩"}) or (message.text all {"承接" "广告"})
 /// ```
-/// **注意**：匹配器中的所有条件之间都没有显式的关系存在，因为匹配器中每一个独立的组之间一定是 `or` 关系，组内的条件之间一定是 `and` 关系。即：已存在隐式的关系表达。
-#[derive(Debug, Default)]
+/// **注意**：通过条件组创建的匹配器中，每一个独立的组之间一定是 `or` 关系，组内的条件之间一定是 `and` 关系。
+/// 需要嵌套或取反整个组时，请使用 [`Expr`](enum.Expr.html) 直接构建表达式。
+///
+/// 匹配过程不会修改匹配器，编译后的匹配器可以通过 `Arc` 在多个线程之间共享。
+#[derive(Debug)]
 pub struct Matcher {
-    /// 条件组序列。
-    pub groups: ContGroups,
-    // 上个组的匹配结果。
-    is_last_match: bool,
+    /// 条件表达式。
+    pub expr: Expr,
 }
 
 impl Matcher {
@@ -170,20 +232,170 @@ impl Matcher {
         Ok(matcher)
     }
 
+    /// 解析规则表达式创建匹配器对象，失败时返回规则中的全部错误。
+    ///
+    /// 与 [`from_rule`](#method.from_rule) 遇到错误立即返回不同，词法、语法和条件的编译错误都会被收集，按照在规则中的位置排序。
+    /// 适合在用户编辑规则时一次性提示全部问题。
+    pub fn from_rule_recovering<S: Into<String>>(rule: S) -> std::result::Result<Self, Vec<Error>> {
+        use super::lexer::Lexer;
+        use super::parser::Parser;
+
+        let input = rule.into().chars().collect::<Vec<_>>();
+        let mut lexer = Lexer::new(&input);
+        let mut errors = lexer.tokenize_recovering();
+        let result = Parser::new(&mut lexer)
+            .map_err(|e| vec![e])
+            .and_then(|parser| parser.parse_recovering());
+
+        match result {
+            Ok(matcher) if errors.is_empty() => Ok(matcher),
+            Ok(_) => Err(errors),
+            Err(parse_errors) => {
+                errors.extend(parse_errors);
+                errors.sort_by_key(|e| e.span().map(|span| span.begin));
+
+                Err(errors)
+            }
+        }
+    }
+
     /// 使用条件组创建匹配器对象。
+    ///
+    /// 条件组之间为 `or` 关系，组内的条件之间为 `and` 关系。
     pub fn new(groups: ContGroups) -> Self {
-        Matcher {
-            groups: groups,
-            is_last_match: true,
+        let exprs = groups
+            .into_iter()
+            .map(|conts| Expr::And(conts.into_iter().map(Expr::Cont).collect()))
+            .collect();
+
+        Matcher::from_expr(Expr::Or(exprs))
+    }
+
+    /// 使用条件表达式创建匹配器对象。
+    pub fn from_expr(expr: Expr) -> Self {
+        Matcher { expr }
+    }
+}
+
+/// 条件表达式。
+///
+/// 表达式是一棵树，叶子节点是单个条件。例如规则 `(a and (b or not (c)))` 对应的表达式为：
+/// ```text
+/// And([Cont(a), Or([Cont(b), Not(Cont(c))])])
+/// ```
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Expr {
+    /// 单个条件。
+    Cont(Cont),
+    /// 取反的表达式。
+    Not(Box<Expr>),
+    /// 具有 `and` 关系的表达式列表。
+    And(Vec<Expr>),
+    /// 具有 `or` 关系的表达式列表。
+    Or(Vec<Expr>),
+}
+
+/// `any` 和 `all` 运算符的关键字。
+#[derive(Debug, Copy, Clone)]
+pub enum Keywords<'a> {
+    /// 逐个查找的关键字列表。
+    Values(&'a Values),
+    /// 预编译的关键字自动机，一次扫描即可找到全部关键字。
+    Automaton(&'a AhoCorasick),
+}
+
+/// 整数字段的 `in` 运算符的值列表。
+#[derive(Debug, Copy, Clone)]
+pub enum Integers<'a> {
+    /// 逐个比较的值列表。
+    Values(&'a Values),
+    /// 预构建的整数集合，查找耗时不随列表长度增长。
+    Set(&'a IntegerSet),
+}
+
+/// 由域名列表构建的集合，用于 `domain_in` 运算符。
+#[derive(Debug)]
+pub struct DomainSet {
+    domains: HashSet<String>,
+    // 通配域名（`*.example.com`）去掉 `*.` 之后的部分。
+    suffixes: HashSet<String>,
+}
+
+impl DomainSet {
+    /// 主机名是否为集合中的域名，或者是通配域名本身及其子域名。
+    pub fn contains(&self, host: &str) -> bool {
+        if self.domains.contains(host) {
+            return true;
+        }
+
+        let mut rest = host;
+        loop {
+            if self.suffixes.contains(rest) {
+                return true;
+            }
+            match rest.find('.') {
+                Some(i) => rest = &rest[i + 1..],
+                None => return false,
+            }
+        }
+    }
+}
+
+/// 由值列表构建的整数集合，列表中的范围仍然逐个比较。
+#[derive(Debug)]
+pub struct IntegerSet {
+    integers: HashSet<i64>,
+    ranges: Vec<Range>,
+}
+
+impl IntegerSet {
+    /// 整数是否等于集合中的某个值，或位于某个范围内。
+    pub fn contains(&self, n: i64) -> Result<bool> {
+        if self.integers.contains(&n) {
+            return Ok(true);
+        }
+
+        for range in &self.ranges {
+            if range.contains_integer(n)? {
+                return Ok(true);
+            }
         }
+
+        Ok(false)
     }
 }
 
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(untagged)
+)]
 pub enum Value {
     Letter(String),
     Integer(i64),
     Decimal(f64),
+    Boolean(bool),
+    Range(Box<Range>),
+    /// 命名列表的引用，匹配时从 [`ListProvider`](trait.ListProvider.html) 中获取。
+    List {
+        name: String,
+    },
+}
+
+/// 数字的闭区间，两端都包含在内。
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Range {
+    /// 起点。
+    pub start: Value,
+    /// 终点。
+    pub end: Value,
 }
 
 /// 单个条件。
@@ -191,12 +403,40 @@ pub enum Value {
 pub struct Cont {
     /// 是否取反。
     pub is_negative: bool,
+    /// 字段所属的消息。
+    pub scope: Scope,
     /// 字段。
     pub field: Field,
     /// 运算符。
     pub operator: Option<Operator>,
     /// 值。
     pub value: Option<Values>,
+    /// 运算符修饰符。
+    pub modifiers: Vec<Modifier>,
+    // 预编译的正则表达式，仅用于 `re` 和 `re_any` 运算符。
+    regexes: Vec<Regex>,
+    // 预编译的关键字自动机，仅用于 `any` 和 `all` 运算符。
+    keywords: Option<AhoCorasick>,
+    // 预构建的整数集合，仅用于整数字段的 `in` 运算符。
+    integers: Option<IntegerSet>,
+    // 预构建的域名集合，仅用于 `domain_in` 运算符。
+    domains: Option<Box<DomainSet>>,
+    // 经过修饰符规范化的值，仅在存在修饰符时使用。
+    normalized_value: Values,
+}
+
+/// 条件字段所属的消息。
+///
+/// 被回复和被置顶的消息的字段以 `message.reply_to_message.` 和 `message.pinned_message.` 起头，
+/// 其余部分与消息本身的字段相同，例如 `message.reply_to_message.from.is_bot`。
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Scope {
+    /// 被匹配的消息本身。
+    Message,
+    /// 被回复的消息。
+    ReplyToMessage,
+    /// 被置顶的消息。
+    PinnedMessage,
 }
 
 /// 条件字段。
@@ -212,7 +452,7 @@ pub enum Field {
     #[strum(serialize = "message.from.first_name")]
     MessageFromFirstName,
     /// 消息来源用户的名。
-    #[strum(serialize = "message.from.first_name")]
+    #[strum(serialize = "message.from.last_name")]
     MessageFromLastName,
     /// 消息来源用户的全名。
     #[strum(serialize = "message.from.full_name")]
@@ -220,6 +460,39 @@ pub enum Field {
     /// 消息来源用户的语言代码。
     #[strum(serialize = "message.from.language_code")]
     MessageFromLanguageCode,
+    /// 消息代表群组或频道发送。
+    #[strum(serialize = "message.sender_chat")]
+    MessageSenderChat,
+    /// 消息代表发送的群组或频道的 ID。
+    #[strum(serialize = "message.sender_chat.id")]
+    MessageSenderChatId,
+    /// 消息代表发送的群组或频道的类型。
+    #[strum(serialize = "message.sender_chat.type")]
+    MessageSenderChatType,
+    /// 消息代表发送的群组或频道的标题。
+    #[strum(serialize = "message.sender_chat.title")]
+    MessageSenderChatTitle,
+    /// 消息转发自用户。
+    #[strum(serialize = "message.forward_from")]
+    MessageForwardFrom,
+    /// 消息的转发源头用户的 ID。
+    #[strum(serialize = "message.forward_from.id")]
+    MessageForwardFromId,
+    /// 消息的转发源头用户是否为 bot。
+    #[strum(serialize = "message.forward_from.is_bot")]
+    MessageForwardFromIsBot,
+    /// 消息的转发源头用户的姓。
+    #[strum(serialize = "message.forward_from.first_name")]
+    MessageForwardFromFirstName,
+    /// 消息的转发源头用户的名。
+    #[strum(serialize = "message.forward_from.last_name")]
+    MessageForwardFromLastName,
+    /// 消息的转发源头用户的全名。
+    #[strum(serialize = "message.forward_from.full_name")]
+    MessageForwardFromFullName,
+    /// 消息的转发源头用户的用户名。
+    #[strum(serialize = "message.forward_from.username")]
+    MessageForwardFromUsername,
     /// 消息来自转发。
     #[strum(serialize = "message.forward_from_chat")]
     MessageForwardFromChat,
@@ -232,9 +505,27 @@ pub enum Field {
     /// 消息的转发源头标题。
     #[strum(serialize = "message.forward_from_chat.title")]
     MessageForwardFromChatTitle,
+    /// 消息的转发源头用户隐藏了账号时显示的名称。
+    #[strum(serialize = "message.forward_sender_name")]
+    MessageForwardSenderName,
+    /// 消息的原始发送时间（Unix 时间戳）。
+    #[strum(serialize = "message.forward_date")]
+    MessageForwardDate,
     /// 消息是对其它消息的回复。
     #[strum(serialize = "message.reply_to_message")]
     MessageReplyToMessage,
+    /// 消息通过 inline bot 发送。
+    #[strum(serialize = "message.via_bot")]
+    MessageViaBot,
+    /// 发送消息的 inline bot 的 ID。
+    #[strum(serialize = "message.via_bot.id")]
+    MessageViaBotId,
+    /// 发送消息的 inline bot 的名称。
+    #[strum(serialize = "message.via_bot.first_name")]
+    MessageViaBotFirstName,
+    /// 发送消息的 inline bot 的用户名。
+    #[strum(serialize = "message.via_bot.username")]
+    MessageViaBotUsername,
     /// 消息中包含文本。
     #[strum(serialize = "message.text")]
     MessageText,
@@ -286,6 +577,27 @@ pub enum Field {
     /// 消息中包含图片。
     #[strum(serialize = "message.photo")]
     MessagePhoto,
+    /// 消息中的图片的尺寸数量。
+    #[strum(serialize = "message.photo.count")]
+    MessagePhotoCount,
+    /// 消息中的图片的任意一个尺寸的宽度。
+    #[strum(serialize = "message.photo.any.width")]
+    MessagePhotoAnyWidth,
+    /// 消息中的图片的全部尺寸的宽度。
+    #[strum(serialize = "message.photo.all.width")]
+    MessagePhotoAllWidth,
+    /// 消息中的图片的任意一个尺寸的高度。
+    #[strum(serialize = "message.photo.any.height")]
+    MessagePhotoAnyHeight,
+    /// 消息中的图片的全部尺寸的高度。
+    #[strum(serialize = "message.photo.all.height")]
+    MessagePhotoAllHeight,
+    /// 消息中的图片的任意一个尺寸的文件大小。
+    #[strum(serialize = "message.photo.any.file_size")]
+    MessagePhotoAnyFileSize,
+    /// 消息中的图片的全部尺寸的文件大小。
+    #[strum(serialize = "message.photo.all.file_size")]
+    MessagePhotoAllFileSize,
     /// 消息中包含贴纸。
     #[strum(serialize = "message.sticker")]
     MessageSticker,
@@ -328,6 +640,36 @@ pub enum Field {
     // 消息中的附件的说明文字的长度。
     #[strum(serialize = "message.caption.len")]
     MessageCaptionLen,
+    /// 消息文本和说明文字中的链接。
+    #[strum(serialize = "message.entities.url")]
+    MessageEntitiesUrl,
+    /// 消息文本和说明文字中提及的用户名（例如 `@username`）。
+    #[strum(serialize = "message.entities.mention")]
+    MessageEntitiesMention,
+    /// 消息文本和说明文字中的话题标签（例如 `#hashtag`）。
+    #[strum(serialize = "message.entities.hashtag")]
+    MessageEntitiesHashtag,
+    /// 消息文本和说明文字中的文字链接所指向的地址。
+    #[strum(serialize = "message.entities.text_link.url")]
+    MessageEntitiesTextLinkUrl,
+    /// 消息文本和说明文字中的实体数量。
+    #[strum(serialize = "message.entities.count")]
+    MessageEntitiesCount,
+    /// 消息文本和说明文字中的任意一个实体的类型。
+    #[strum(serialize = "message.entities.any.type")]
+    MessageEntitiesAnyType,
+    /// 消息文本和说明文字中的全部实体的类型。
+    #[strum(serialize = "message.entities.all.type")]
+    MessageEntitiesAllType,
+    /// 消息文本和说明文字中的链接的主机名。
+    #[strum(serialize = "message.urls.host")]
+    MessageUrlsHost,
+    /// 消息文本和说明文字中的链接的可注册域名，例如 `www.example.com` 的 `example.com`。
+    #[strum(serialize = "message.urls.domain")]
+    MessageUrlsDomain,
+    /// 消息文本和说明文字中的链接的路径。
+    #[strum(serialize = "message.urls.path")]
+    MessageUrlsPath,
     // 消息中包含骰子。
     #[strum(serialize = "message.dice")]
     MessageDice,
@@ -361,6 +703,27 @@ pub enum Field {
     // 消息中包含新成员。
     #[strum(serialize = "message.new_chat_members")]
     MessageNewChatMembers,
+    // 消息中的新成员数量。
+    #[strum(serialize = "message.new_chat_members.count")]
+    MessageNewChatMembersCount,
+    // 消息中的任意一个新成员的 ID。
+    #[strum(serialize = "message.new_chat_members.any.id")]
+    MessageNewChatMembersAnyId,
+    // 消息中的全部新成员的 ID。
+    #[strum(serialize = "message.new_chat_members.all.id")]
+    MessageNewChatMembersAllId,
+    // 消息中的任意一个新成员是否为 bot。
+    #[strum(serialize = "message.new_chat_members.any.is_bot")]
+    MessageNewChatMembersAnyIsBot,
+    // 消息中的全部新成员是否为 bot。
+    #[strum(serialize = "message.new_chat_members.all.is_bot")]
+    MessageNewChatMembersAllIsBot,
+    // 消息中的任意一个新成员的全名。
+    #[strum(serialize = "message.new_chat_members.any.full_name")]
+    MessageNewChatMembersAnyFullName,
+    // 消息中的全部新成员的全名。
+    #[strum(serialize = "message.new_chat_members.all.full_name")]
+    MessageNewChatMembersAllFullName,
     // 消息中包含已退出（包括被移除）的成员。
     #[strum(serialize = "message.left_chat_member")]
     MessageLeftChatMember,
@@ -379,29 +742,22 @@ pub enum Field {
     // 消息是否为命令。
     #[strum(serialize = "message.is_command")]
     MessageIsCommand,
+    // 消息是否为转发消息（包括转发自用户、频道和隐藏了账号的用户）。
+    #[strum(serialize = "message.is_forwarded")]
+    MessageIsForwarded,
 }
 
 pub trait GetSingleValue {
     fn get_a_str_ref(&self) -> Result<&str>;
     fn get_an_integer(&self) -> Result<i64>;
     fn get_a_decimal(&self) -> Result<f64>;
+    fn get_a_boolean(&self) -> Result<bool>;
+    fn get_a_range(&self) -> Result<&Range>;
 }
 pub trait RefAnInteger {
     fn ref_an_integer(&self) -> Result<&i64>;
 }
 
-impl ToString for Value {
-    fn to_string(&self) -> String {
-        use Value::*;
-
-        match self {
-            Letter(v) => v.to_owned(),
-            Integer(v) => v.to_string(),
-            Decimal(v) => v.to_string(),
-        }
-    }
-}
-
 impl GetSingleValue for Value {
     fn get_a_str_ref(&self) -> Result<&str> {
         use Value::*;
@@ -436,6 +792,28 @@ impl GetSingleValue for Value {
             }),
         }
     }
+
+    fn get_a_boolean(&self) -> Result<bool> {
+        use Value::*;
+
+        match self {
+            Boolean(v) => Ok(*v),
+            _ => Err(Error::NotABoolean {
+                value: self.clone(),
+            }),
+        }
+    }
+
+    fn get_a_range(&self) -> Result<&Range> {
+        use Value::*;
+
+        match self {
+            Range(v) => Ok(v),
+            _ => Err(Error::NotARange {
+                value: self.clone(),
+            }),
+        }
+    }
 }
 
 impl GetSingleValue for Values {
@@ -462,12 +840,56 @@ impl GetSingleValue for Values {
             Err(Error::RefValueInEmptyList)
         }
     }
+
+    fn get_a_boolean(&self) -> Result<bool> {
+        if let Some(first) = self.first() {
+            first.get_a_boolean()
+        } else {
+            Err(Error::RefValueInEmptyList)
+        }
+    }
+
+    fn get_a_range(&self) -> Result<&Range> {
+        if let Some(first) = self.first() {
+            first.get_a_range()
+        } else {
+            Err(Error::RefValueInEmptyList)
+        }
+    }
 }
 
 impl Value {
     pub fn from_str(value_s: &str) -> Self {
         Value::Letter(value_s.to_owned())
     }
+
+    /// 整数是否等于此值，或位于此范围内。
+    pub fn contains_integer(&self, n: i64) -> Result<bool> {
+        match self {
+            Value::Range(range) => range.contains_integer(n),
+            v => Ok(v.get_an_integer()? == n),
+        }
+    }
+
+    /// 小数是否等于此值，或位于此范围内。
+    pub fn contains_decimal(&self, n: f64) -> Result<bool> {
+        match self {
+            Value::Range(range) => range.contains_decimal(n),
+            v => Ok(v.get_a_decimal()? == n),
+        }
+    }
+}
+
+impl Range {
+    /// 整数是否位于此范围内。
+    pub fn contains_integer(&self, n: i64) -> Result<bool> {
+        Ok(self.start.get_an_integer()? <= n && n <= self.end.get_an_integer()?)
+    }
+
+    /// 小数是否位于此范围内。
+    pub fn contains_decimal(&self, n: f64) -> Result<bool> {
+        Ok(self.start.get_a_decimal()? <= n && n <= self.end.get_a_decimal()?)
+    }
 }
 
 impl Cont {
@@ -478,15 +900,47 @@ impl Cont {
         operator_str: String,
         value: Values,
     ) -> Result<Self> {
-        let operator =
-            Operator::from_str(operator_str.as_str()).map_err(|_| Error::UnknownOperator {
-                operator: operator_str.to_owned(),
-            })?;
+        // 运算符之后可能附加以 `.` 分隔的修饰符。
+        let mut operator_parts = operator_str.split('.');
+        let operator_name = operator_parts.next().unwrap_or_default();
+        let operator = Operator::from_str(operator_name).map_err(|_| {
+            // 建议时保留修饰符部分。
+            let suggestion = did_you_mean(operator_name, Operator::iter().map(|o| o.to_string()))
+                .map(|s| format!("{}{}", s, &operator_str[operator_name.len()..]));
 
-        let field = Field::from_str(field_str.as_str()).map_err(|_| Error::UnknownField {
-            field: field_str.to_owned(),
+            Error::UnknownOperator {
+                operator: operator_str.to_owned(),
+                suggestion,
+            }
         })?;
+        let modifiers = operator_parts
+            .map(|m| {
+                Modifier::from_str(m).map_err(|_| Error::UnknownModifier {
+                    modifier: m.to_owned(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let (scope, field) = Scope::parse_field(&field_str)?;
+
+        let cont = Cont::build(is_negative, field, operator, value)?.with_scope(scope);
 
+        if modifiers.is_empty() {
+            Ok(cont)
+        } else {
+            cont.with_modifiers(modifiers)
+        }
+    }
+
+    /// 使用字段、运算符和值构建条件。
+    ///
+    /// 运算符需要的预处理（例如编译正则表达式）会在此时完成，匹配时不再重复。
+    pub fn build(
+        is_negative: bool,
+        field: Field,
+        operator: Operator,
+        value: Values,
+    ) -> Result<Self> {
         let operators = FIELD_OPERATORS
             .get(&field)
             .copied()
@@ -498,18 +952,102 @@ impl Cont {
             return Err(Error::UnsupportedOperator { field, operator });
         }
 
+        // 命名列表的内容在匹配时才能确定，此时只检查运算符。
+        let is_list = list_name(&value).is_some();
+        if !is_list {
+            check_values(field, operator, &value)?;
+        } else if !matches!(
+            operator,
+            Operator::In | Operator::Any | Operator::All | Operator::DomainIn
+        ) {
+            return Err(Error::UnsupportedList { operator });
+        }
+
+        let regexes = match operator {
+            Operator::Re | Operator::ReAny => compile_regexes(&value)?,
+            _ => vec![],
+        };
+        let keywords = match operator {
+            Operator::Any | Operator::All if !is_list => compile_keywords(&value)?,
+            _ => None,
+        };
+        let integers = match operator {
+            Operator::In if !is_list && !field.is_text() && !field.is_decimal() => {
+                compile_integers(&value)?
+            }
+            _ => None,
+        };
+        let domains = match operator {
+            Operator::DomainIn if !is_list => Some(Box::new(compile_domains(&value)?)),
+            _ => None,
+        };
+
         Ok(Cont {
             is_negative,
+            scope: Scope::Message,
             field,
             operator: Some(operator),
             value: Some(value),
+            modifiers: vec![],
+            regexes,
+            keywords,
+            integers,
+            domains,
+            normalized_value: vec![],
         })
     }
 
+    /// 设置字段所属的消息。
+    pub fn with_scope(mut self, scope: Scope) -> Self {
+        self.scope = scope;
+
+        self
+    }
+
+    /// 为条件附加运算符修饰符。
+    ///
+    /// 仅文本字段的 `eq`、`in`、`any`、`all`、`hd` 和 `td` 运算符支持修饰符。值会在此时完成规范化。
+    pub fn with_modifiers(mut self, mut modifiers: Vec<Modifier>) -> Result<Self> {
+        let operator = *self.operator()?;
+        let supported = matches!(
+            operator,
+            Operator::Eq
+                | Operator::In
+                | Operator::Any
+                | Operator::All
+                | Operator::Hd
+                | Operator::Td
+        );
+
+        if !supported || !self.field.is_text() {
+            return Err(Error::UnsupportedModifier {
+                field: self.field,
+                operator,
+            });
+        }
+
+        modifiers.sort();
+        modifiers.dedup();
+
+        let mut normalized_value = vec![];
+        // 命名列表在匹配时才规范化。
+        if self.list_name().is_none() {
+            for v in self.value()? {
+                normalized_value.push(Value::Letter(normalize(v.get_a_str_ref()?, &modifiers)));
+            }
+        }
+
+        if self.keywords.is_some() {
+            self.keywords = compile_keywords(&normalized_value)?;
+        }
+        self.modifiers = modifiers;
+        self.normalized_value = normalized_value;
+
+        Ok(self)
+    }
+
     pub fn single_field(is_negative: bool, field_str: String) -> Result<Self> {
-        let field = Field::from_str(field_str.as_str()).map_err(|_| Error::UnknownField {
-            field: field_str.to_owned(),
-        })?;
+        let (scope, field) = Scope::parse_field(&field_str)?;
 
         let _operators = FIELD_OPERATORS
             .get(&field)
@@ -517,11 +1055,23 @@ impl Cont {
             // 没有注册运算符列表，表示字段未启用。
             .ok_or(Error::FieldNotEndabled { field })?;
 
+        // 只有布尔字段可以单独作为条件。
+        if !field.is_boolean() {
+            return Err(Error::FieldRequireOperator { field });
+        }
+
         Ok(Cont {
             is_negative,
+            scope,
             field,
             operator: None,
             value: None,
+            modifiers: vec![],
+            regexes: vec![],
+            keywords: None,
+            integers: None,
+            domains: None,
+            normalized_value: vec![],
         })
     }
 
@@ -533,6 +1083,27 @@ impl Cont {
         }
     }
 
+    /// 条件引用的命名列表的名称。
+    pub fn list_name(&self) -> Option<&str> {
+        self.value.as_ref().and_then(list_name)
+    }
+
+    // 获取命名列表按照当前条件的字段、运算符和修饰符编译的条件，列表为空时返回 `None`。
+    fn resolve_list(&self, ctx: &Context, name: &str) -> Result<Option<Arc<Cont>>> {
+        let list = ctx
+            .lists
+            .and_then(|lists| lists.list(name))
+            .ok_or_else(|| Error::UnknownList {
+                name: name.to_owned(),
+            })?;
+        if list.values().is_empty() {
+            return Ok(None);
+        }
+
+        list.compile(self.field, *self.operator()?, &self.modifiers)
+            .map(Some)
+    }
+
     fn value(&self) -> Result<&Values> {
         if let Some(value) = &self.value {
             Ok(value)
@@ -540,217 +1111,1536 @@ impl Cont {
             Err(Error::FieldRequireValue { field: self.field })
         }
     }
+
+    fn domains(&self) -> Result<&DomainSet> {
+        self.domains.as_deref().ok_or(Error::RefValueInEmptyList)
+    }
+
+    fn regex(&self) -> Result<&Regex> {
+        self.regexes.first().ok_or(Error::RefValueInEmptyList)
+    }
+
+    fn regexes(&self) -> &[Regex] {
+        &self.regexes
+    }
+
+    fn keywords(&self) -> Result<Keywords<'_>> {
+        if let Some(automaton) = &self.keywords {
+            Ok(Keywords::Automaton(automaton))
+        } else if self.modifiers.is_empty() {
+            Ok(Keywords::Values(self.value()?))
+        } else {
+            Ok(Keywords::Values(&self.normalized_value))
+        }
+    }
+
+    fn integers(&self) -> Result<Integers<'_>> {
+        if let Some(set) = &self.integers {
+            Ok(Integers::Set(set))
+        } else {
+            Ok(Integers::Values(self.value()?))
+        }
+    }
 }
 
-impl Matcher {
-    pub fn match_message(&mut self, message: &Message) -> Result<bool> {
-        self.loop_match(message, 0)
+// 编译值列表中的全部正则表达式。
+fn compile_regexes(value: &Values) -> Result<Vec<Regex>> {
+    let mut regexes = vec![];
+
+    for v in value {
+        let pattern = v.get_a_str_ref()?;
+        let regex = Regex::new(pattern).map_err(|e| Error::InvalidRegex {
+            pattern: pattern.to_owned(),
+            source: e,
+        })?;
+
+        regexes.push(regex);
     }
 
-    fn loop_match(&mut self, message: &Message, position: usize) -> Result<bool> {
-        if position > 0 && self.is_last_match {
-            return Ok(true);
+    Ok(regexes)
+}
+
+// 检查值的数量和类型。布尔字段的值必须是布尔值，文本字段的值必须是字符串，其余字段的值必须是数字（小数字段也接受整数）。
+fn check_values(field: Field, operator: Operator, value: &Values) -> Result<()> {
+    if operator.takes_list() {
+        if value.is_empty() {
+            return Err(Error::EmptyList { operator });
         }
-        if position > (self.groups.len() - 1) {
-            return Ok(self.is_last_match);
+    } else if value.len() != 1 {
+        return Err(Error::ExpectedSingleValue { operator });
+    }
+
+    for v in value {
+        if field.is_boolean() {
+            v.get_a_boolean()?;
+        } else if field.is_text() {
+            v.get_a_str_ref()?;
+        } else if operator == Operator::Between {
+            check_range(field, v.get_a_range()?)?;
+        } else if let (Operator::In, Value::Range(range)) = (operator, v) {
+            // `in` 的列表中可以混合数字和范围。
+            check_range(field, range)?;
+        } else {
+            check_number(field, v)?;
         }
+    }
 
-        let conts = unsafe { self.groups.get_unchecked(position) };
+    Ok(())
+}
 
-        let mut result = true;
-        for cont in conts {
-            if !cont.match_message(message)? {
-                result = false;
-                break;
-            }
+// 值是否为单个命名列表的引用。
+fn list_name(value: &Values) -> Option<&str> {
+    match value.as_slice() {
+        [Value::List { name }] => Some(name),
+        _ => None,
+    }
+}
+
+// 检查范围两端的数字类型，并确保起点不大于终点。
+fn check_range(field: Field, range: &Range) -> Result<()> {
+    check_number(field, &range.start)?;
+    check_number(field, &range.end)?;
+
+    if range.start.get_a_decimal()? > range.end.get_a_decimal()? {
+        return Err(Error::InvalidRange {
+            value: Value::Range(Box::new(range.clone())),
+        });
+    }
+
+    Ok(())
+}
+
+fn check_number(field: Field, value: &Value) -> Result<()> {
+    if field.is_decimal() {
+        value.get_a_decimal()?;
+    } else {
+        value.get_an_integer()?;
+    }
+
+    Ok(())
+}
+
+// 将值列表中的全部关键字编译为自动机，重复的关键字只保留一个。关键字较少时不编译。
+fn compile_keywords(value: &Values) -> Result<Option<AhoCorasick>> {
+    if value.len() < KEYWORDS_AUTOMATON_THRESHOLD {
+        return Ok(None);
+    }
+
+    let mut patterns = vec![];
+    let mut seen = HashSet::new();
+
+    for v in value {
+        let pattern = v.get_a_str_ref()?;
+        if seen.insert(pattern) {
+            patterns.push(pattern);
         }
-        self.is_last_match = result;
-        self.loop_match(message, position + 1)
     }
+
+    let automaton =
+        AhoCorasick::new(patterns).map_err(|e| Error::BuildAutomatonFailed { source: e })?;
+
+    Ok(Some(automaton))
 }
 
-// 检查子字段是否为存在或为真。
-//
-// 第一个参数为 `Option<T>` 类型。如果为 `None` 则返回 `false`，否则进一步判断。
-// 如果第一个参数存在值，将通过值访问子级字段并获取 `is_truthy` 方法的调用结果。
-macro_rules! child_is_truthy {
-    ($optinal:expr, $child:tt) => {
-        if let Some(parent) = $optinal {
-            parent.$child.is_truthy()
+// 将值列表中的域名构建为集合。域名不区分大小写，`*.` 开头的是通配域名。
+fn compile_domains(value: &Values) -> Result<DomainSet> {
+    let mut domains = HashSet::new();
+    let mut suffixes = HashSet::new();
+
+    for v in value {
+        let pattern = v.get_a_str_ref()?;
+        let domain = pattern.trim_end_matches('.').to_lowercase();
+        let (domain, is_wildcard) = match domain.strip_prefix("*.") {
+            Some(suffix) => (suffix.to_owned(), true),
+            None => (domain, false),
+        };
+
+        let is_valid = !domain.is_empty()
+            && !domain.starts_with('.')
+            && !domain.contains(|c: char| c.is_whitespace() || matches!(c, '*' | '/' | ':'));
+        if !is_valid {
+            return Err(Error::InvalidDomain {
+                pattern: pattern.to_owned(),
+            });
+        }
+
+        if is_wildcard {
+            suffixes.insert(domain);
         } else {
-            false
+            domains.insert(domain);
         }
-    };
+    }
+
+    Ok(DomainSet { domains, suffixes })
 }
 
-// unwrap_or_falsey_hosting
-macro_rules! ufh {
-    ($optinal:expr) => {
-        $optinal.unwrap_or_falsey_hosting()?
-    };
+// 将值列表中的整数构建为哈希集合，范围单独保留。整数较少时不构建。
+fn compile_integers(value: &Values) -> Result<Option<IntegerSet>> {
+    if value.len() < INTEGER_SET_THRESHOLD {
+        return Ok(None);
+    }
+
+    let mut integers = HashSet::with_capacity(value.len());
+    let mut ranges = vec![];
+
+    for v in value {
+        match v {
+            Value::Range(range) => ranges.push((**range).clone()),
+            v => {
+                integers.insert(v.get_an_integer()?);
+            }
+        }
+    }
+
+    Ok(Some(IntegerSet { integers, ranges }))
 }
 
-impl Cont {
-    pub fn match_message(&self, message: &Message) -> Result<bool> {
-        let unsupported_operator_err = || -> Result<Error> {
-            Ok(Error::UnsupportedOperator {
-                field: self.field,
-                operator: *self.operator()?,
-            })
+// 消息文本和说明文字中的实体总数。
+fn entity_count(message: &Message) -> i64 {
+    len_of(&message.entities) + len_of(&message.caption_entities)
+}
+
+// 可选列表的长度，不存在时为 0。
+fn len_of<T>(list: &Option<Vec<T>>) -> i64 {
+    list.as_ref().map_or(0, Vec::len) as i64
+}
+
+/// 预先编译的命名列表。
+///
+/// 列表第一次被条件使用时，按照条件的字段、运算符和修饰符检查并编译（包括构建关键字自动机、哈希集合和域名集合），
+/// 编译结果会被缓存，之后的匹配直接复用。列表通过 `Arc` 共享，更新列表时替换为新的 `CompiledList` 即可。
+#[derive(Debug)]
+pub struct CompiledList {
+    values: Values,
+    // 按照字段、运算符和修饰符缓存的编译结果。
+    conts: RwLock<CompiledConts>,
+}
+
+impl CompiledList {
+    /// 使用值列表创建命名列表，值在第一次被使用时才检查和编译。
+    pub fn new(values: Values) -> Self {
+        CompiledList {
+            values,
+            conts: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 列表中的值。
+    pub fn values(&self) -> &Values {
+        &self.values
+    }
+
+    // 获取为指定的字段、运算符和修饰符编译的条件，第一次获取时编译。
+    fn compile(
+        &self,
+        field: Field,
+        operator: Operator,
+        modifiers: &[Modifier],
+    ) -> Result<Arc<Cont>> {
+        let key = (field, operator, modifiers.to_vec());
+        if let Some(cont) = read_lock(&self.conts).get(&key) {
+            return Ok(cont.clone());
+        }
+
+        // 列表中不能再引用其它列表，因此先检查值。
+        check_values(field, operator, &self.values)?;
+        // 匹配上下文已经切换到字段所属的消息。
+        let cont = Cont::build(false, field, operator, self.values.clone())?;
+        let cont = if modifiers.is_empty() {
+            cont
+        } else {
+            cont.with_modifiers(modifiers.to_vec())?
         };
 
-        let r = match self.field {
-            Field::MessageFromId => match self.operator()? {
-                Operator::Eq => ufh!(message.from).id.eq_ope(self.value()?),
-                Operator::Gt => ufh!(message.from).id.gt_ope(self.value()?),
-                Operator::Ge => ufh!(message.from).id.ge_ope(self.value()?),
-                Operator::Le => ufh!(message.from).id.le_ope(self.value()?),
-                _ => Err(unsupported_operator_err()?),
-            },
+        let mut conts = self.conts.write().unwrap_or_else(PoisonError::into_inner);
+        Ok(conts.entry(key).or_insert_with(|| Arc::new(cont)).clone())
+    }
+}
+
+impl From<Values> for CompiledList {
+    fn from(values: Values) -> Self {
+        CompiledList::new(values)
+    }
+}
+
+/// 命名列表的提供者。
+///
+/// 规则中以 `$名称` 引用的列表在匹配时才从提供者中获取，因此更新列表不需要重新编译规则。
+pub trait ListProvider {
+    /// 获取指定名称的列表，不存在时返回 `None`。
+    fn list(&self, name: &str) -> Option<Arc<CompiledList>>;
+}
+
+impl ListProvider for HashMap<String, Arc<CompiledList>> {
+    fn list(&self, name: &str) -> Option<Arc<CompiledList>> {
+        self.get(name).cloned()
+    }
+}
+
+/// 可在匹配的同时被其它线程更新的提供者。
+impl<P: ListProvider> ListProvider for RwLock<P> {
+    fn list(&self, name: &str) -> Option<Arc<CompiledList>> {
+        read_lock(self).list(name)
+    }
+}
+
+// 获取读锁。持有锁的线程崩溃时数据仍然完整，因此忽略中毒状态。
+fn read_lock<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// 匹配上下文。
+///
+/// 在一条消息的匹配过程中共享，缓存由消息计算得到的数据，避免每个条件重复计算。缓存的数据包括：
+/// 规范化的文本和拼接的全名、实体和链接字段的内容、解析出的链接、列表字段的元素的值，
+/// 以及被回复和被置顶的消息的上下文。
+pub struct Context<'a> {
+    /// 被匹配的消息。
+    pub message: &'a Message,
+    // 命名列表的提供者。
+    lists: Option<&'a dyn ListProvider>,
+    // 已规范化的文本字段。
+    normalized_texts: RefCell<NormalizedTexts>,
+    // 多值字段的内容。
+    contents: RefCell<Contents>,
+    // 量化字段的元素的值。
+    items: RefCell<Items>,
+    // 消息中解析出的链接。
+    urls: RefCell<Option<Rc<Vec<Url>>>>,
+    // 被回复和被置顶的消息的上下文，消息不存在时为 `None`。
+    scopes: RefCell<HashMap<Scope, Option<Rc<Context<'a>>>>>,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(message: &'a Message) -> Self {
+        Context {
+            message,
+            lists: None,
+            normalized_texts: RefCell::new(HashMap::new()),
+            contents: RefCell::new(HashMap::new()),
+            items: RefCell::new(HashMap::new()),
+            urls: RefCell::new(None),
+            scopes: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// 创建可以获取命名列表的匹配上下文。
+    pub fn with_lists(message: &'a Message, lists: &'a dyn ListProvider) -> Self {
+        Context {
+            lists: Some(lists),
+            ..Context::new(message)
+        }
+    }
+
+    // 获取同一次匹配中被回复或被置顶的消息的上下文，命名列表保持不变。消息不存在时返回 `None`。
+    fn scoped(&self, scope: Scope) -> Option<Rc<Context<'a>>> {
+        if let Some(ctx) = self.scopes.borrow().get(&scope) {
+            return ctx.clone();
+        }
+
+        let ctx = scope.message(self.message).map(|message| {
+            Rc::new(Context {
+                lists: self.lists,
+                ..Context::new(message)
+            })
+        });
+        self.scopes.borrow_mut().insert(scope, ctx.clone());
+
+        ctx
+    }
+
+    // 获取经过修饰符规范化的文本字段内容，同一条消息只计算一次。
+    pub(crate) fn normalized_text(
+        &self,
+        field: Field,
+        modifiers: &[Modifier],
+    ) -> Result<Rc<Option<String>>> {
+        memoize(&self.normalized_texts, (field, modifiers.to_vec()), || {
+            Ok(field
+                .text(self.message)?
+                .map(|text| normalize(&text, modifiers)))
+        })
+    }
+
+    // 获取需要计算的文本字段（例如全名）的内容，同一条消息只计算一次。
+    pub(crate) fn text(&self, field: Field) -> Result<Rc<Option<String>>> {
+        self.normalized_text(field, &[])
+    }
+
+    // 获取多值字段的全部内容，同一条消息只读取一次。
+    pub(crate) fn contents(&self, field: Field) -> Rc<Vec<String>> {
+        let contents = memoize(&self.contents, field, || Ok(field.contents(self)));
+
+        contents.unwrap_or_default()
+    }
+
+    // 获取量化字段的元素的值，同一条消息只读取一次。
+    pub(crate) fn items(&self, field: Field) -> Rc<Vec<Option<Value>>> {
+        let items = memoize(&self.items, field, || Ok(field.items(self.message)));
+
+        items.unwrap_or_default()
+    }
+
+    // 获取消息中的全部链接，包括文本和说明文字中的链接、链接实体和文字链接指向的地址。
+    fn urls(&self) -> Rc<Vec<Url>> {
+        if let Some(urls) = &*self.urls.borrow() {
+            return Rc::clone(urls);
+        }
+
+        let message = self.message;
+        let entity_urls = self.contents(Field::MessageEntitiesUrl);
+        let text_link_urls = self.contents(Field::MessageEntitiesTextLinkUrl);
+        let urls = [&message.text, &message.caption]
+            .iter()
+            .copied()
+            .flatten()
+            .flat_map(|text| find_urls(text))
+            .chain(entity_urls.iter().map(String::as_str))
+            .chain(text_link_urls.iter().map(String::as_str))
+            .filter_map(Url::parse)
+            .collect::<Vec<_>>();
+        let urls = Rc::new(urls);
+        *self.urls.borrow_mut() = Some(Rc::clone(&urls));
+
+        urls
+    }
+}
+
+// 读取缓存的值，不存在时计算并缓存。计算时不持有缓存的借用，因此计算过程中可以读取其它缓存。
+fn memoize<K: Eq + Hash, V>(
+    cache: &RefCell<HashMap<K, Rc<V>>>,
+    key: K,
+    f: impl FnOnce() -> Result<V>,
+) -> Result<Rc<V>> {
+    if let Some(v) = cache.borrow().get(&key) {
+        return Ok(Rc::clone(v));
+    }
+
+    let v = Rc::new(f()?);
+    cache.borrow_mut().insert(key, Rc::clone(&v));
+
+    Ok(v)
+}
+
+impl Matcher {
+    pub fn match_message(&self, message: &Message) -> Result<bool> {
+        self.expr.match_context(&Context::new(message))
+    }
+
+    /// 匹配消息，规则中引用的命名列表从 `lists` 中获取。
+    pub fn match_message_with_lists(
+        &self,
+        message: &Message,
+        lists: &dyn ListProvider,
+    ) -> Result<bool> {
+        self.expr
+            .match_context(&Context::with_lists(message, lists))
+    }
+}
+
+impl Expr {
+    pub fn match_message(&self, message: &Message) -> Result<bool> {
+        self.match_context(&Context::new(message))
+    }
+
+    /// 在匹配上下文中匹配。
+    pub fn match_context(&self, ctx: &Context) -> Result<bool> {
+        match self {
+            Expr::Cont(cont) => cont.match_context(ctx),
+            Expr::Not(expr) => Ok(!expr.match_context(ctx)?),
+            Expr::And(exprs) => {
+                for expr in exprs {
+                    if !expr.match_context(ctx)? {
+                        return Ok(false);
+                    }
+                }
+
+                Ok(true)
+            }
+            Expr::Or(exprs) => {
+                for expr in exprs {
+                    if expr.match_context(ctx)? {
+                        return Ok(true);
+                    }
+                }
+
+                Ok(false)
+            }
+        }
+    }
+}
+
+// 检查子字段是否为存在或为真。
+//
+// 第一个参数为 `Option<T>` 类型。如果为 `None` 则返回 `false`，否则进一步判断。
+// 如果第一个参数存在值，将通过值访问子级字段并获取 `is_truthy` 方法的调用结果。
+macro_rules! child_is_truthy {
+    ($optinal:expr, $child:tt) => {
+        if let Some(parent) = $optinal {
+            parent.$child.is_truthy()
+        } else {
+            false
+        }
+    };
+}
+
+// unwrap_or_falsey_hosting
+macro_rules! ufh {
+    ($optinal:expr) => {
+        $optinal.unwrap_or_falsey_hosting()?
+    };
+}
+
+impl Scope {
+    /// 解析字段路径，返回字段所属的消息和字段本身。
+    pub fn parse_field(field_str: &str) -> Result<(Self, Field)> {
+        for scope in [Scope::ReplyToMessage, Scope::PinnedMessage].iter() {
+            let rest = match field_str.strip_prefix(scope.prefix()) {
+                Some(rest) if rest.starts_with('.') => rest,
+                _ => continue,
+            };
+
+            // 拼写建议同样保留所属消息的前缀。
+            let field = Field::parse(&format!("message{}", rest)).map_err(|e| match e {
+                Error::UnknownField { suggestion, .. } => Error::UnknownField {
+                    field: field_str.to_owned(),
+                    suggestion: suggestion
+                        .map(|s| format!("{}{}", scope.prefix(), &s["message".len()..])),
+                },
+                e => e,
+            })?;
+
+            return Ok((*scope, field));
+        }
+
+        Ok((Scope::Message, Field::parse(field_str)?))
+    }
+
+    /// 字段在规则中的完整路径，例如 `message.reply_to_message.text`。
+    pub fn field_path(&self, field: Field) -> String {
+        let field = field.to_string();
+
+        format!("{}{}", self.prefix(), &field["message".len()..])
+    }
+
+    // 字段路径中代表所属消息的部分。
+    fn prefix(&self) -> &'static str {
+        match self {
+            Scope::Message => "message",
+            Scope::ReplyToMessage => "message.reply_to_message",
+            Scope::PinnedMessage => "message.pinned_message",
+        }
+    }
+
+    // 读取所属的消息，不存在时返回 `None`。
+    fn message<'a>(&self, message: &'a Message) -> Option<&'a Message> {
+        match self {
+            Scope::Message => Some(message),
+            Scope::ReplyToMessage => message.reply_to_message.as_deref(),
+            Scope::PinnedMessage => message.pinned_message.as_deref(),
+        }
+    }
+}
+
+impl Field {
+    /// 解析字段，未知的字段会附带拼写建议。
+    fn parse(field_str: &str) -> Result<Self> {
+        Field::from_str(field_str).map_err(|_| Error::UnknownField {
+            field: field_str.to_owned(),
+            suggestion: did_you_mean(field_str, FIELD_OPERATORS.keys().map(|f| f.to_string())),
+        })
+    }
+
+    /// 字段支持的运算符。未启用的字段和不接受运算符的字段返回空列表。
+    pub fn operators(&self) -> &'static [Operator] {
+        FIELD_OPERATORS.get(self).copied().unwrap_or_default()
+    }
+
+    /// 是否为布尔字段。
+    ///
+    /// 布尔字段可以单独作为条件（判断真假或是否存在），也可以使用 `eq` 与布尔值比较。
+    pub fn is_boolean(&self) -> bool {
+        matches!(
+            self,
+            Field::MessageFromIsBot
+                | Field::MessageSenderChat
+                | Field::MessageForwardFrom
+                | Field::MessageForwardFromIsBot
+                | Field::MessageForwardFromChat
+                | Field::MessageReplyToMessage
+                | Field::MessageViaBot
+                | Field::MessageAnimation
+                | Field::MessageAudio
+                | Field::MessageDocument
+                | Field::MessagePhoto
+                | Field::MessageSticker
+                | Field::MessageStickerIsAnimated
+                | Field::MessageVideo
+                | Field::MessageVoice
+                | Field::MessageDice
+                | Field::MessagePoll
+                | Field::MessageVenue
+                | Field::MessageLocation
+                | Field::MessageNewChatMembers
+                | Field::MessageNewChatMembersAnyIsBot
+                | Field::MessageNewChatMembersAllIsBot
+                | Field::MessageLeftChatMember
+                | Field::MessageNewChatTitle
+                | Field::MessageNewChatPhoto
+                | Field::MessagePinnedMessage
+                | Field::MessageIsServiceMessage
+                | Field::MessageIsCommand
+                | Field::MessageIsForwarded
+        )
+    }
+
+    /// 是否为小数字段。
+    pub fn is_decimal(&self) -> bool {
+        matches!(
+            self,
+            Field::MessageLocationLongitude | Field::MessageLocationLatitude
+        )
+    }
+
+    /// 是否为文本字段。
+    pub fn is_text(&self) -> bool {
+        matches!(
+            self,
+            Field::MessageFromFirstName
+                | Field::MessageFromLastName
+                | Field::MessageFromFullName
+                | Field::MessageFromLanguageCode
+                | Field::MessageSenderChatType
+                | Field::MessageSenderChatTitle
+                | Field::MessageForwardFromFirstName
+                | Field::MessageForwardFromLastName
+                | Field::MessageForwardFromFullName
+                | Field::MessageForwardFromUsername
+                | Field::MessageForwardFromChatType
+                | Field::MessageForwardFromChatTitle
+                | Field::MessageForwardSenderName
+                | Field::MessageViaBotFirstName
+                | Field::MessageViaBotUsername
+                | Field::MessageText
+                | Field::MessageAnimationFileName
+                | Field::MessageAnimationMimeType
+                | Field::MessageAudioPerformer
+                | Field::MessageAudioMimeType
+                | Field::MessageDocumentFileName
+                | Field::MessageDocumentMimeType
+                | Field::MessageStickerEmoji
+                | Field::MessageStickerSetName
+                | Field::MessageVideoMimeType
+                | Field::MessageVoiceMimeType
+                | Field::MessageCaption
+                | Field::MessageDiceEmoji
+                | Field::MessagePollType
+                | Field::MessageVenueTitle
+                | Field::MessageVenueAddress
+                | Field::MessageEntitiesUrl
+                | Field::MessageEntitiesMention
+                | Field::MessageEntitiesHashtag
+                | Field::MessageEntitiesTextLinkUrl
+                | Field::MessageUrlsHost
+                | Field::MessageUrlsDomain
+                | Field::MessageUrlsPath
+                | Field::MessageEntitiesAnyType
+                | Field::MessageEntitiesAllType
+                | Field::MessageNewChatMembersAnyFullName
+                | Field::MessageNewChatMembersAllFullName
+        )
+    }
+
+    /// 是否为多值字段（实体和链接字段）。多值字段任意一个值满足条件即匹配。
+    pub fn is_multiple(&self) -> bool {
+        matches!(
+            self,
+            Field::MessageEntitiesUrl
+                | Field::MessageEntitiesMention
+                | Field::MessageEntitiesHashtag
+                | Field::MessageEntitiesTextLinkUrl
+                | Field::MessageUrlsHost
+                | Field::MessageUrlsDomain
+                | Field::MessageUrlsPath
+        )
+    }
+
+    /// 是否为量化字段（列表中每一个元素的字段）。
+    ///
+    /// `any` 字段任意一个元素满足条件即匹配，`all` 字段要求全部元素都满足条件。列表为空时两者都不匹配。
+    pub fn is_quantified(&self) -> bool {
+        matches!(
+            self,
+            Field::MessagePhotoAnyWidth
+                | Field::MessagePhotoAllWidth
+                | Field::MessagePhotoAnyHeight
+                | Field::MessagePhotoAllHeight
+                | Field::MessagePhotoAnyFileSize
+                | Field::MessagePhotoAllFileSize
+                | Field::MessageEntitiesAnyType
+                | Field::MessageEntitiesAllType
+                | Field::MessageNewChatMembersAnyId
+                | Field::MessageNewChatMembersAllId
+                | Field::MessageNewChatMembersAnyIsBot
+                | Field::MessageNewChatMembersAllIsBot
+                | Field::MessageNewChatMembersAnyFullName
+                | Field::MessageNewChatMembersAllFullName
+        )
+    }
+
+    // 量化字段是否要求全部元素都满足条件。
+    fn is_universal(&self) -> bool {
+        matches!(
+            self,
+            Field::MessagePhotoAllWidth
+                | Field::MessagePhotoAllHeight
+                | Field::MessagePhotoAllFileSize
+                | Field::MessageEntitiesAllType
+                | Field::MessageNewChatMembersAllId
+                | Field::MessageNewChatMembersAllIsBot
+                | Field::MessageNewChatMembersAllFullName
+        )
+    }
+
+    // 读取量化字段在列表中每一个元素上的值，元素缺少该值时为 `None`。非量化字段返回空列表。
+    fn items(&self, message: &Message) -> Vec<Option<Value>> {
+        let sizes = message.photo.iter().flatten();
+        let entities = message
+            .entities
+            .iter()
+            .chain(&message.caption_entities)
+            .flatten();
+        let members = message.new_chat_members.iter().flatten();
+        let integer = |n: i64| Some(Value::Integer(n));
+
+        match self {
+            Field::MessagePhotoAnyWidth | Field::MessagePhotoAllWidth => {
+                sizes.map(|p| integer(p.width as i64)).collect()
+            }
+            Field::MessagePhotoAnyHeight | Field::MessagePhotoAllHeight => {
+                sizes.map(|p| integer(p.height as i64)).collect()
+            }
+            Field::MessagePhotoAnyFileSize | Field::MessagePhotoAllFileSize => sizes
+                .map(|p| p.file_size.and_then(|n| integer(n as i64)))
+                .collect(),
+            Field::MessageEntitiesAnyType | Field::MessageEntitiesAllType => entities
+                .map(|e| Some(Value::Letter(e.type_.clone())))
+                .collect(),
+            Field::MessageNewChatMembersAnyId | Field::MessageNewChatMembersAllId => {
+                members.map(|u| integer(u.id)).collect()
+            }
+            Field::MessageNewChatMembersAnyIsBot | Field::MessageNewChatMembersAllIsBot => {
+                members.map(|u| Some(Value::Boolean(u.is_bot))).collect()
+            }
+            Field::MessageNewChatMembersAnyFullName | Field::MessageNewChatMembersAllFullName => {
+                members
+                    .map(|u| Some(Value::Letter(u.full_name())))
+                    .collect()
+            }
+            _ => vec![],
+        }
+    }
+
+    // 读取多值字段的全部内容，非多值字段返回空列表。链接从上下文中读取，只解析一次。
+    fn contents(&self, ctx: &Context) -> Vec<String> {
+        let part = |url: &Url| match self {
+            Field::MessageUrlsHost => Some(url.host.clone()),
+            Field::MessageUrlsDomain => Some(url.domain().to_owned()),
+            Field::MessageUrlsPath => Some(url.path.clone()),
+            _ => None,
+        };
+
+        match self {
+            Field::MessageUrlsHost | Field::MessageUrlsDomain | Field::MessageUrlsPath => {
+                let mut contents = vec![];
+                for content in ctx.urls().iter().filter_map(part) {
+                    if !contents.contains(&content) {
+                        contents.push(content);
+                    }
+                }
+
+                contents
+            }
+            _ => self.entities(ctx.message),
+        }
+    }
+
+    // 读取实体字段的全部内容，包括消息文本和说明文字中的实体。非实体字段返回空列表。
+    fn entities(&self, message: &Message) -> Vec<String> {
+        let entity_type = match self {
+            Field::MessageEntitiesUrl => "url",
+            Field::MessageEntitiesMention => "mention",
+            Field::MessageEntitiesHashtag => "hashtag",
+            Field::MessageEntitiesTextLinkUrl => "text_link",
+            _ => return vec![],
+        };
+        let sources = [
+            (&message.text, &message.entities),
+            (&message.caption, &message.caption_entities),
+        ];
+
+        let mut contents = vec![];
+        for (source, entities) in sources.iter() {
+            let entities = entities.iter().flatten().filter(|e| e.type_ == entity_type);
+            // 实体的位置以 UTF-16 计算，每个文本只编码一次。
+            let units = source
+                .as_ref()
+                .map(|s| s.encode_utf16().collect::<Vec<_>>());
+
+            for entity in entities {
+                let content = match (self, &units) {
+                    (Field::MessageEntitiesTextLinkUrl, _) => entity.url.clone(),
+                    (_, Some(units)) => entity.text_from_utf16(units),
+                    (_, None) => None,
+                };
+
+                contents.extend(content);
+            }
+        }
+
+        contents
+    }
+
+    // 读取文本字段的内容，非文本字段返回 `None`。多值字段的内容通过 `contents` 读取。
+    fn text<'a>(&self, message: &'a Message) -> Result<Option<Cow<'a, str>>> {
+        let borrowed = |s: &'a String| Some(Cow::Borrowed(s.as_str()));
+        let optional = |s: &'a Option<String>| s.as_deref().map(Cow::Borrowed);
+
+        let text = match self {
+            Field::MessageFromFirstName => borrowed(&ufh!(message.from).first_name),
+            Field::MessageFromLastName => optional(&ufh!(message.from).last_name),
+            Field::MessageFromFullName => Some(Cow::Owned(ufh!(message.from).full_name())),
+            Field::MessageFromLanguageCode => optional(&ufh!(message.from).language_code),
+            Field::MessageSenderChatType => borrowed(&ufh!(message.sender_chat).type_),
+            Field::MessageSenderChatTitle => optional(&ufh!(message.sender_chat).title),
+            Field::MessageForwardFromFirstName => borrowed(&ufh!(message.forward_from).first_name),
+            Field::MessageForwardFromLastName => optional(&ufh!(message.forward_from).last_name),
+            Field::MessageForwardFromFullName => {
+                Some(Cow::Owned(ufh!(message.forward_from).full_name()))
+            }
+            Field::MessageForwardFromUsername => optional(&ufh!(message.forward_from).username),
+            Field::MessageForwardFromChatType => borrowed(&ufh!(message.forward_from_chat).type_),
+            Field::MessageForwardFromChatTitle => optional(&ufh!(message.forward_from_chat).title),
+            Field::MessageForwardSenderName => optional(&message.forward_sender_name),
+            Field::MessageViaBotFirstName => borrowed(&ufh!(message.via_bot).first_name),
+            Field::MessageViaBotUsername => optional(&ufh!(message.via_bot).username),
+            Field::MessageText => optional(&message.text),
+            Field::MessageAnimationFileName => optional(&ufh!(message.animation).file_name),
+            Field::MessageAnimationMimeType => optional(&ufh!(message.animation).mime_type),
+            Field::MessageAudioPerformer => optional(&ufh!(message.audio).performer),
+            Field::MessageAudioMimeType => optional(&ufh!(message.audio).mime_type),
+            Field::MessageDocumentFileName => optional(&ufh!(message.document).file_name),
+            Field::MessageDocumentMimeType => optional(&ufh!(message.document).mime_type),
+            Field::MessageStickerEmoji => optional(&ufh!(message.sticker).emoji),
+            Field::MessageStickerSetName => optional(&ufh!(message.sticker).set_name),
+            Field::MessageVideoMimeType => optional(&ufh!(message.video).mime_type),
+            Field::MessageVoiceMimeType => optional(&ufh!(message.voice).mime_type),
+            Field::MessageCaption => optional(&message.caption),
+            Field::MessageDiceEmoji => borrowed(&ufh!(message.dice).emoji),
+            Field::MessagePollType => borrowed(&ufh!(message.poll).type_),
+            Field::MessageVenueTitle => borrowed(&ufh!(message.venue).title),
+            Field::MessageVenueAddress => borrowed(&ufh!(message.venue).address),
+            _ => None,
+        };
+
+        Ok(text)
+    }
+
+    /// 读取字段的值，用于解释匹配结果。
+    ///
+    /// 字段不存在时返回 `None`。没有运算符的字段只表示真假，不读取值，同样返回 `None`。
+    /// 实体和链接字段返回提取出的全部内容，以空格连接；没有内容时返回 `None`。
+    pub fn resolve(&self, message: &Message) -> Result<Option<Value>> {
+        self.resolve_context(&Context::new(message))
+    }
+
+    /// 在匹配上下文中读取字段的值，实体和链接的提取结果与匹配共用。
+    pub fn resolve_context(&self, ctx: &Context) -> Result<Option<Value>> {
+        let message = ctx.message;
+        if self.is_multiple() {
+            let contents = ctx.contents(*self);
+            if contents.is_empty() {
+                return Ok(None);
+            }
+
+            return Ok(Some(Value::Letter(contents.join(" "))));
+        }
+        if self.is_text() {
+            return Ok(self.text(message)?.map(|t| Value::Letter(t.into_owned())));
+        }
+        if self.is_boolean() {
+            let cont = Cont::single_field(false, self.to_string())?;
+
+            return Ok(Some(Value::Boolean(cont.match_message(message)?)));
+        }
+
+        let integer = |n: i64| Some(Value::Integer(n));
+        let size = |n: Option<i32>| n.map(|n| Value::Integer(n as i64));
+        let len = |s: &Option<String>| s.as_ref().map(|s| Value::Integer(s.chars().count() as i64));
+
+        let value = match self {
+            Field::MessageFromId => integer(ufh!(message.from).id),
+            Field::MessageSenderChatId => integer(ufh!(message.sender_chat).id),
+            Field::MessageForwardFromId => integer(ufh!(message.forward_from).id),
+            Field::MessageForwardFromChatId => integer(ufh!(message.forward_from_chat).id),
+            Field::MessageForwardDate => message.forward_date.and_then(integer),
+            Field::MessageViaBotId => integer(ufh!(message.via_bot).id),
+            Field::MessageTextLen => len(&message.text),
+            Field::MessageAnimationDuration => integer(ufh!(message.animation).duration as i64),
+            Field::MessageAnimationFileSize => size(ufh!(message.animation).file_size),
+            Field::MessageAudioDuration => integer(ufh!(message.audio).duration as i64),
+            Field::MessageAudioFileSize => size(ufh!(message.audio).file_size),
+            Field::MessageDocumentFileSize => size(ufh!(message.document).file_size),
+            Field::MessageVideoDuration => integer(ufh!(message.video).duration as i64),
+            Field::MessageVideoFileSize => size(ufh!(message.video).file_size),
+            Field::MessageVoiceDuration => integer(ufh!(message.voice).duration as i64),
+            Field::MessageVoiceFileSize => size(ufh!(message.voice).file_size),
+            Field::MessageCaptionLen => len(&message.caption),
+            Field::MessagePhotoCount => integer(len_of(&message.photo)),
+            Field::MessageEntitiesCount => integer(entity_count(message)),
+            Field::MessageNewChatMembersCount => integer(len_of(&message.new_chat_members)),
+            Field::MessageLocationLongitude => {
+                Some(Value::Decimal(ufh!(message.location).longitude))
+            }
+            Field::MessageLocationLatitude => Some(Value::Decimal(ufh!(message.location).latitude)),
+            _ => None,
+        };
+
+        Ok(value)
+    }
+}
+
+impl Cont {
+    pub fn match_message(&self, message: &Message) -> Result<bool> {
+        self.match_context(&Context::new(message))
+    }
+
+    /// 在匹配上下文中匹配。
+    pub fn match_context(&self, ctx: &Context) -> Result<bool> {
+        let r = self.in_scope(ctx, |ctx| {
+            if let Some(name) = self.list_name() {
+                self.match_list(ctx, name)
+            } else if self.field.is_quantified() {
+                self.match_items(ctx)
+            } else if self.field.is_boolean() {
+                self.match_boolean(ctx)
+            } else if self.field.is_multiple() {
+                self.match_contents(ctx)
+            } else if self.modifiers.is_empty() {
+                self.match_field(ctx)
+            } else {
+                self.match_normalized_text(ctx)
+            }
+        });
+
+        match r {
+            Ok(no_negative) => {
+                if self.is_negative {
+                    Ok(!no_negative)
+                } else {
+                    Ok(no_negative)
+                }
+            }
+            Err(Error::FalsyValueHosting) => {
+                if self.is_negative {
+                    Ok(true)
+                } else {
+                    Ok(false)
+                }
+            }
+            e => e,
+        }
+    }
+
+    /// 在字段所属的消息的上下文中求值，所属的消息不存在时返回 `FalsyValueHosting` 错误。
+    pub fn in_scope<T>(&self, ctx: &Context, f: impl FnOnce(&Context) -> Result<T>) -> Result<T> {
+        match self.scope {
+            Scope::Message => f(ctx),
+            scope => {
+                let ctx = ctx.scoped(scope).ok_or(Error::FalsyValueHosting)?;
+
+                f(&ctx)
+            }
+        }
+    }
+
+    // 匹配布尔字段。单独的字段判断其真假，`eq` 运算符则将其与布尔值比较。
+    fn match_boolean(&self, ctx: &Context) -> Result<bool> {
+        let truthy = match self.match_field(ctx) {
+            Err(Error::FalsyValueHosting) => false,
+            r => r?,
+        };
+
+        match self.operator {
+            Some(Operator::Eq) => Ok(truthy == self.value()?.get_a_boolean()?),
+            Some(operator) => Err(Error::UnsupportedOperator {
+                field: self.field,
+                operator,
+            }),
+            None => Ok(truthy),
+        }
+    }
+
+    // 使用命名列表匹配，空列表不匹配任何内容。
+    fn match_list(&self, ctx: &Context, name: &str) -> Result<bool> {
+        match self.resolve_list(ctx, name)? {
+            Some(cont) => cont.match_context(ctx),
+            None => Ok(false),
+        }
+    }
+
+    // 匹配多值字段，任意一个值满足条件即匹配。修饰符同样生效。
+    fn match_contents(&self, ctx: &Context) -> Result<bool> {
+        for content in ctx.contents(self.field).iter() {
+            let matched = if self.modifiers.is_empty() {
+                self.match_text(content)?
+            } else {
+                self.match_text(&normalize(content, &self.modifiers))?
+            };
+
+            if matched {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    // 匹配量化字段，没有元素时不匹配。
+    fn match_items(&self, ctx: &Context) -> Result<bool> {
+        let items = ctx.items(self.field);
+        if items.is_empty() {
+            return Ok(false);
+        }
+
+        let is_universal = self.field.is_universal();
+        for item in items.iter() {
+            let matched = match item {
+                Some(item) => self.match_item(item)?,
+                None => false,
+            };
+
+            match (is_universal, matched) {
+                (false, true) => return Ok(true),
+                (true, false) => return Ok(false),
+                _ => (),
+            }
+        }
+
+        Ok(is_universal)
+    }
+
+    // 匹配量化字段中的单个元素的值。布尔值没有运算符时判断其真假。
+    fn match_item(&self, item: &Value) -> Result<bool> {
+        match item {
+            Value::Letter(content) if self.modifiers.is_empty() => self.match_text(content),
+            Value::Letter(content) => self.match_text(&normalize(content, &self.modifiers)),
+            Value::Integer(n) => self.match_integer(*n),
+            Value::Boolean(b) => match self.operator {
+                Some(Operator::Eq) => Ok(*b == self.value()?.get_a_boolean()?),
+                Some(operator) => Err(Error::UnsupportedOperator {
+                    field: self.field,
+                    operator,
+                }),
+                None => Ok(*b),
+            },
+            _ => Ok(false),
+        }
+    }
+
+    // 使用数字运算符匹配给定的整数。
+    fn match_integer(&self, n: i64) -> Result<bool> {
+        match self.operator()? {
+            Operator::Eq => n.eq_ope(self.value()?),
+            Operator::Gt => n.gt_ope(self.value()?),
+            Operator::Lt => n.lt_ope(self.value()?),
+            Operator::Ge => n.ge_ope(self.value()?),
+            Operator::Le => n.le_ope(self.value()?),
+            Operator::In => n.in_ope(self.integers()?),
+            Operator::Between => n.between_ope(self.value()?),
+            operator => Err(Error::UnsupportedOperator {
+                field: self.field,
+                operator: *operator,
+            }),
+        }
+    }
+
+    // 使用文本运算符匹配给定的内容。存在修饰符时内容应当已被规范化。
+    fn match_text(&self, content: &String) -> Result<bool> {
+        let value = if self.modifiers.is_empty() {
+            self.value()?
+        } else {
+            &self.normalized_value
+        };
+
+        match self.operator()? {
+            Operator::Eq => content.eq_ope(value),
+            Operator::In => content.in_ope(value),
+            Operator::Any => content.any_ope(self.keywords()?),
+            Operator::All => content.all_ope(self.keywords()?),
+            Operator::Hd => content.hd_ope(value),
+            Operator::Td => content.td_ope(value),
+            Operator::Re => content.re_ope(self.regex()?),
+            Operator::ReAny => content.re_any_ope(self.regexes()),
+            Operator::DomainIn => content.domain_in_ope(self.domains()?),
+            operator => Err(Error::UnsupportedOperator {
+                field: self.field,
+                operator: *operator,
+            }),
+        }
+    }
+
+    // 使用规范化的字段内容和值匹配。
+    fn match_normalized_text(&self, ctx: &Context) -> Result<bool> {
+        let text = ctx.normalized_text(self.field, &self.modifiers)?;
+        let value = &self.normalized_value;
+
+        match self.operator()? {
+            Operator::Eq => text.eq_ope(value),
+            Operator::In => text.in_ope(value),
+            Operator::Any => text.any_ope(self.keywords()?),
+            Operator::All => text.all_ope(self.keywords()?),
+            Operator::Hd => text.hd_ope(value),
+            Operator::Td => text.td_ope(value),
+            operator => Err(Error::UnsupportedModifier {
+                field: self.field,
+                operator: *operator,
+            }),
+        }
+    }
+
+    // 计算 `any` 和 `all` 运算符命中的值，修饰符同样生效。返回的是条件中的原始值。
+    pub(crate) fn hits(&self, ctx: &Context) -> Result<Vec<Value>> {
+        if !matches!(self.operator, Some(Operator::Any) | Some(Operator::All)) {
+            return Ok(vec![]);
+        }
+        if let Some(name) = self.list_name() {
+            return match self.resolve_list(ctx, name)? {
+                Some(cont) => cont.hits(ctx),
+                None => Ok(vec![]),
+            };
+        }
+
+        let targets = if self.modifiers.is_empty() {
+            self.value()?
+        } else {
+            &self.normalized_value
+        };
+        // 多值字段和量化字段的任意一个值包含关键字即命中。
+        let texts = if self.field.is_multiple() {
+            ctx.contents(self.field)
+                .iter()
+                .map(|content| normalize(content, &self.modifiers))
+                .collect()
+        } else if self.field.is_quantified() {
+            ctx.items(self.field)
+                .iter()
+                .flatten()
+                .filter_map(|item| item.get_a_str_ref().ok())
+                .map(|content| normalize(content, &self.modifiers))
+                .collect()
+        } else if self.modifiers.is_empty() {
+            self.field
+                .text(ctx.message)?
+                .map(Cow::into_owned)
+                .into_iter()
+                .collect::<Vec<_>>()
+        } else {
+            let text = ctx.normalized_text(self.field, &self.modifiers)?;
+
+            text.as_ref().clone().into_iter().collect()
+        };
+
+        let mut hits = vec![];
+        for (target, v) in targets.iter().zip(self.value()?) {
+            let target = target.get_a_str_ref()?;
+            if texts.iter().any(|text| text.contains(target)) {
+                hits.push(v.clone());
+            }
+        }
+
+        Ok(hits)
+    }
+
+    fn match_field(&self, ctx: &Context) -> Result<bool> {
+        let message = ctx.message;
+        let unsupported_operator_err = || -> Result<Error> {
+            Ok(Error::UnsupportedOperator {
+                field: self.field,
+                operator: *self.operator()?,
+            })
+        };
+
+        match self.field {
+            Field::MessageFromId => match self.operator()? {
+                Operator::Eq => ufh!(message.from).id.eq_ope(self.value()?),
+                Operator::Gt => ufh!(message.from).id.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.from).id.lt_ope(self.value()?),
+                Operator::Ge => ufh!(message.from).id.ge_ope(self.value()?),
+                Operator::Le => ufh!(message.from).id.le_ope(self.value()?),
+                Operator::In => ufh!(message.from).id.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.from).id.between_ope(self.value()?),
+                _ => Err(unsupported_operator_err()?),
+            },
             Field::MessageFromIsBot => Ok(child_is_truthy!(&message.from, is_bot)),
             Field::MessageFromFirstName => match self.operator()? {
                 Operator::Eq => ufh!(message.from).first_name.eq_ope(self.value()?),
                 Operator::In => ufh!(message.from).first_name.in_ope(self.value()?),
-                Operator::Any => ufh!(message.from).first_name.any_ope(self.value()?),
-                Operator::All => ufh!(message.from).first_name.all_ope(self.value()?),
+                Operator::Any => ufh!(message.from).first_name.any_ope(self.keywords()?),
+                Operator::All => ufh!(message.from).first_name.all_ope(self.keywords()?),
                 Operator::Hd => ufh!(message.from).first_name.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.from).first_name.td_ope(self.value()?),
+                Operator::Re => ufh!(message.from).first_name.re_ope(self.regex()?),
+                Operator::ReAny => ufh!(message.from).first_name.re_any_ope(self.regexes()),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageFromLastName => match self.operator()? {
                 Operator::Eq => ufh!(message.from).last_name.eq_ope(self.value()?),
                 Operator::In => ufh!(message.from).last_name.in_ope(self.value()?),
-                Operator::Any => ufh!(message.from).last_name.any_ope(self.value()?),
-                Operator::All => ufh!(message.from).last_name.all_ope(self.value()?),
+                Operator::Any => ufh!(message.from).last_name.any_ope(self.keywords()?),
+                Operator::All => ufh!(message.from).last_name.all_ope(self.keywords()?),
                 Operator::Hd => ufh!(message.from).last_name.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.from).last_name.td_ope(self.value()?),
+                Operator::Re => ufh!(message.from).last_name.re_ope(self.regex()?),
+                Operator::ReAny => ufh!(message.from).last_name.re_any_ope(self.regexes()),
                 _ => Err(unsupported_operator_err()?),
             },
-            Field::MessageFromFullName => match self.operator()? {
-                Operator::Eq => ufh!(message.from).full_name().eq_ope(self.value()?),
-                Operator::In => ufh!(message.from).full_name().in_ope(self.value()?),
-                Operator::Any => ufh!(message.from).full_name().any_ope(self.value()?),
-                Operator::All => ufh!(message.from).full_name().all_ope(self.value()?),
-                Operator::Hd => ufh!(message.from).full_name().hd_ope(self.value()?),
-                _ => Err(unsupported_operator_err()?),
-            },
+            Field::MessageFromFullName => {
+                // 全名需要拼接，同一条消息只计算一次。
+                let full_name = ctx.text(self.field)?;
+
+                match self.operator()? {
+                    Operator::Eq => full_name.eq_ope(self.value()?),
+                    Operator::In => full_name.in_ope(self.value()?),
+                    Operator::Any => full_name.any_ope(self.keywords()?),
+                    Operator::All => full_name.all_ope(self.keywords()?),
+                    Operator::Hd => full_name.hd_ope(self.value()?),
+                    Operator::Td => full_name.td_ope(self.value()?),
+                    Operator::Re => full_name.re_ope(self.regex()?),
+                    Operator::ReAny => full_name.re_any_ope(self.regexes()),
+                    _ => Err(unsupported_operator_err()?),
+                }
+            }
             Field::MessageFromLanguageCode => match self.operator()? {
                 Operator::Eq => ufh!(message.from).language_code.eq_ope(self.value()?),
                 Operator::In => ufh!(message.from).language_code.in_ope(self.value()?),
                 Operator::Hd => ufh!(message.from).language_code.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.from).language_code.td_ope(self.value()?),
+                _ => Err(unsupported_operator_err()?),
+            },
+            Field::MessageSenderChat => Ok(message.sender_chat.is_truthy()),
+            Field::MessageSenderChatId => match self.operator()? {
+                Operator::Eq => ufh!(message.sender_chat).id.eq_ope(self.value()?),
+                Operator::Gt => ufh!(message.sender_chat).id.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.sender_chat).id.lt_ope(self.value()?),
+                Operator::Ge => ufh!(message.sender_chat).id.ge_ope(self.value()?),
+                Operator::Le => ufh!(message.sender_chat).id.le_ope(self.value()?),
+                Operator::In => ufh!(message.sender_chat).id.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.sender_chat).id.between_ope(self.value()?),
+                _ => Err(unsupported_operator_err()?),
+            },
+            Field::MessageSenderChatType => match self.operator()? {
+                Operator::Eq => ufh!(message.sender_chat).type_.eq_ope(self.value()?),
+                Operator::In => ufh!(message.sender_chat).type_.in_ope(self.value()?),
+                Operator::Td => ufh!(message.sender_chat).type_.td_ope(self.value()?),
+                _ => Err(unsupported_operator_err()?),
+            },
+            Field::MessageSenderChatTitle => match self.operator()? {
+                Operator::Eq => ufh!(message.sender_chat).title.eq_ope(self.value()?),
+                Operator::Any => ufh!(message.sender_chat).title.any_ope(self.keywords()?),
+                Operator::All => ufh!(message.sender_chat).title.all_ope(self.keywords()?),
+                Operator::Hd => ufh!(message.sender_chat).title.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.sender_chat).title.td_ope(self.value()?),
+                _ => Err(unsupported_operator_err()?),
+            },
+            Field::MessageForwardFrom => Ok(message.forward_from.is_truthy()),
+            Field::MessageForwardFromId => match self.operator()? {
+                Operator::Eq => ufh!(message.forward_from).id.eq_ope(self.value()?),
+                Operator::Gt => ufh!(message.forward_from).id.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.forward_from).id.lt_ope(self.value()?),
+                Operator::Ge => ufh!(message.forward_from).id.ge_ope(self.value()?),
+                Operator::Le => ufh!(message.forward_from).id.le_ope(self.value()?),
+                Operator::In => ufh!(message.forward_from).id.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.forward_from).id.between_ope(self.value()?),
+                _ => Err(unsupported_operator_err()?),
+            },
+            Field::MessageForwardFromIsBot => Ok(child_is_truthy!(&message.forward_from, is_bot)),
+            Field::MessageForwardFromFirstName => match self.operator()? {
+                Operator::Eq => ufh!(message.forward_from).first_name.eq_ope(self.value()?),
+                Operator::In => ufh!(message.forward_from).first_name.in_ope(self.value()?),
+                Operator::Any => ufh!(message.forward_from)
+                    .first_name
+                    .any_ope(self.keywords()?),
+                Operator::All => ufh!(message.forward_from)
+                    .first_name
+                    .all_ope(self.keywords()?),
+                Operator::Hd => ufh!(message.forward_from).first_name.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.forward_from).first_name.td_ope(self.value()?),
+                Operator::Re => ufh!(message.forward_from).first_name.re_ope(self.regex()?),
+                Operator::ReAny => ufh!(message.forward_from)
+                    .first_name
+                    .re_any_ope(self.regexes()),
+                _ => Err(unsupported_operator_err()?),
+            },
+            Field::MessageForwardFromLastName => match self.operator()? {
+                Operator::Eq => ufh!(message.forward_from).last_name.eq_ope(self.value()?),
+                Operator::In => ufh!(message.forward_from).last_name.in_ope(self.value()?),
+                Operator::Any => ufh!(message.forward_from)
+                    .last_name
+                    .any_ope(self.keywords()?),
+                Operator::All => ufh!(message.forward_from)
+                    .last_name
+                    .all_ope(self.keywords()?),
+                Operator::Hd => ufh!(message.forward_from).last_name.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.forward_from).last_name.td_ope(self.value()?),
+                Operator::Re => ufh!(message.forward_from).last_name.re_ope(self.regex()?),
+                Operator::ReAny => ufh!(message.forward_from)
+                    .last_name
+                    .re_any_ope(self.regexes()),
+                _ => Err(unsupported_operator_err()?),
+            },
+            Field::MessageForwardFromFullName => {
+                // 全名需要拼接，同一条消息只计算一次。
+                let full_name = ctx.text(self.field)?;
+
+                match self.operator()? {
+                    Operator::Eq => full_name.eq_ope(self.value()?),
+                    Operator::In => full_name.in_ope(self.value()?),
+                    Operator::Any => full_name.any_ope(self.keywords()?),
+                    Operator::All => full_name.all_ope(self.keywords()?),
+                    Operator::Hd => full_name.hd_ope(self.value()?),
+                    Operator::Td => full_name.td_ope(self.value()?),
+                    Operator::Re => full_name.re_ope(self.regex()?),
+                    Operator::ReAny => full_name.re_any_ope(self.regexes()),
+                    _ => Err(unsupported_operator_err()?),
+                }
+            }
+            Field::MessageForwardFromUsername => match self.operator()? {
+                Operator::Eq => ufh!(message.forward_from).username.eq_ope(self.value()?),
+                Operator::In => ufh!(message.forward_from).username.in_ope(self.value()?),
+                Operator::Hd => ufh!(message.forward_from).username.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.forward_from).username.td_ope(self.value()?),
+                Operator::Re => ufh!(message.forward_from).username.re_ope(self.regex()?),
+                Operator::ReAny => ufh!(message.forward_from)
+                    .username
+                    .re_any_ope(self.regexes()),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageForwardFromChat => Ok(message.forward_from_chat.is_truthy()),
             Field::MessageForwardFromChatId => match self.operator()? {
                 Operator::Eq => ufh!(message.forward_from_chat).id.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.forward_from_chat).id.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.forward_from_chat).id.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.forward_from_chat).id.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.forward_from_chat).id.le_ope(self.value()?),
+                Operator::In => ufh!(message.forward_from_chat).id.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.forward_from_chat)
+                    .id
+                    .between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageForwardFromChatType => match self.operator()? {
                 Operator::Eq => ufh!(message.forward_from_chat).type_.eq_ope(self.value()?),
                 Operator::In => ufh!(message.forward_from_chat).type_.in_ope(self.value()?),
+                Operator::Td => ufh!(message.forward_from_chat).type_.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageForwardFromChatTitle => match self.operator()? {
                 Operator::Eq => ufh!(message.forward_from_chat).title.eq_ope(self.value()?),
-                Operator::Any => ufh!(message.forward_from_chat).title.any_ope(self.value()?),
-                Operator::All => ufh!(message.forward_from_chat).title.all_ope(self.value()?),
+                Operator::Any => ufh!(message.forward_from_chat)
+                    .title
+                    .any_ope(self.keywords()?),
+                Operator::All => ufh!(message.forward_from_chat)
+                    .title
+                    .all_ope(self.keywords()?),
                 Operator::Hd => ufh!(message.forward_from_chat).title.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.forward_from_chat).title.td_ope(self.value()?),
+                _ => Err(unsupported_operator_err()?),
+            },
+            Field::MessageForwardSenderName => match self.operator()? {
+                Operator::Eq => message.forward_sender_name.eq_ope(self.value()?),
+                Operator::In => message.forward_sender_name.in_ope(self.value()?),
+                Operator::Any => message.forward_sender_name.any_ope(self.keywords()?),
+                Operator::All => message.forward_sender_name.all_ope(self.keywords()?),
+                Operator::Hd => message.forward_sender_name.hd_ope(self.value()?),
+                Operator::Td => message.forward_sender_name.td_ope(self.value()?),
+                Operator::Re => message.forward_sender_name.re_ope(self.regex()?),
+                Operator::ReAny => message.forward_sender_name.re_any_ope(self.regexes()),
+                _ => Err(unsupported_operator_err()?),
+            },
+            Field::MessageForwardDate => match self.operator()? {
+                Operator::Eq => ufh!(message.forward_date).eq_ope(self.value()?),
+                Operator::Gt => ufh!(message.forward_date).gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.forward_date).lt_ope(self.value()?),
+                Operator::Ge => ufh!(message.forward_date).ge_ope(self.value()?),
+                Operator::Le => ufh!(message.forward_date).le_ope(self.value()?),
+                Operator::In => ufh!(message.forward_date).in_ope(self.integers()?),
+                Operator::Between => ufh!(message.forward_date).between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageReplyToMessage => Ok(message.reply_to_message.is_truthy()),
+            Field::MessageViaBot => Ok(message.via_bot.is_truthy()),
+            Field::MessageViaBotId => match self.operator()? {
+                Operator::Eq => ufh!(message.via_bot).id.eq_ope(self.value()?),
+                Operator::Gt => ufh!(message.via_bot).id.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.via_bot).id.lt_ope(self.value()?),
+                Operator::Ge => ufh!(message.via_bot).id.ge_ope(self.value()?),
+                Operator::Le => ufh!(message.via_bot).id.le_ope(self.value()?),
+                Operator::In => ufh!(message.via_bot).id.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.via_bot).id.between_ope(self.value()?),
+                _ => Err(unsupported_operator_err()?),
+            },
+            Field::MessageViaBotFirstName => match self.operator()? {
+                Operator::Eq => ufh!(message.via_bot).first_name.eq_ope(self.value()?),
+                Operator::In => ufh!(message.via_bot).first_name.in_ope(self.value()?),
+                Operator::Any => ufh!(message.via_bot).first_name.any_ope(self.keywords()?),
+                Operator::All => ufh!(message.via_bot).first_name.all_ope(self.keywords()?),
+                Operator::Hd => ufh!(message.via_bot).first_name.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.via_bot).first_name.td_ope(self.value()?),
+                Operator::Re => ufh!(message.via_bot).first_name.re_ope(self.regex()?),
+                Operator::ReAny => ufh!(message.via_bot).first_name.re_any_ope(self.regexes()),
+                _ => Err(unsupported_operator_err()?),
+            },
+            Field::MessageViaBotUsername => match self.operator()? {
+                Operator::Eq => ufh!(message.via_bot).username.eq_ope(self.value()?),
+                Operator::In => ufh!(message.via_bot).username.in_ope(self.value()?),
+                Operator::Hd => ufh!(message.via_bot).username.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.via_bot).username.td_ope(self.value()?),
+                Operator::Re => ufh!(message.via_bot).username.re_ope(self.regex()?),
+                Operator::ReAny => ufh!(message.via_bot).username.re_any_ope(self.regexes()),
+                _ => Err(unsupported_operator_err()?),
+            },
             Field::MessageText => match self.operator()? {
                 Operator::Eq => message.text.eq_ope(self.value()?),
                 Operator::In => message.text.in_ope(self.value()?),
-                Operator::Any => message.text.any_ope(self.value()?),
-                Operator::All => message.text.all_ope(self.value()?),
+                Operator::Any => message.text.any_ope(self.keywords()?),
+                Operator::All => message.text.all_ope(self.keywords()?),
+                Operator::Hd => message.text.hd_ope(self.value()?),
+                Operator::Td => message.text.td_ope(self.value()?),
+                Operator::Re => message.text.re_ope(self.regex()?),
+                Operator::ReAny => message.text.re_any_ope(self.regexes()),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageTextLen => match self.operator()? {
                 Operator::Eq => message.text.eq_ope_for_content_len(self.value()?),
                 Operator::Gt => message.text.gt_ope_for_content_len(self.value()?),
+                Operator::Lt => message.text.lt_ope_for_content_len(self.value()?),
                 Operator::Ge => message.text.ge_ope_for_content_len(self.value()?),
                 Operator::Le => message.text.le_ope_for_content_len(self.value()?),
+                Operator::In => message.text.in_ope_for_content_len(self.integers()?),
+                Operator::Between => message.text.between_ope_for_content_len(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageAnimation => Ok(message.animation.is_truthy()),
             Field::MessageAnimationDuration => match self.operator()? {
                 Operator::Eq => ufh!(message.animation).duration.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.animation).duration.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.animation).duration.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.animation).duration.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.animation).duration.le_ope(self.value()?),
+                Operator::In => ufh!(message.animation).duration.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.animation).duration.between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageAnimationFileName => match self.operator()? {
                 Operator::Eq => ufh!(message.animation).file_name.eq_ope(self.value()?),
-                Operator::Any => ufh!(message.animation).file_name.any_ope(self.value()?),
-                Operator::All => ufh!(message.animation).file_name.all_ope(self.value()?),
+                Operator::Any => ufh!(message.animation).file_name.any_ope(self.keywords()?),
+                Operator::All => ufh!(message.animation).file_name.all_ope(self.keywords()?),
                 Operator::Hd => ufh!(message.animation).file_name.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.animation).file_name.td_ope(self.value()?),
+                Operator::Re => ufh!(message.animation).file_name.re_ope(self.regex()?),
+                Operator::ReAny => ufh!(message.animation).file_name.re_any_ope(self.regexes()),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageAnimationMimeType => match self.operator()? {
                 Operator::Eq => ufh!(message.animation).mime_type.eq_ope(self.value()?),
                 Operator::In => ufh!(message.animation).mime_type.in_ope(self.value()?),
                 Operator::Hd => ufh!(message.animation).mime_type.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.animation).mime_type.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageAnimationFileSize => match self.operator()? {
                 Operator::Eq => ufh!(message.animation).file_size.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.animation).file_size.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.animation).file_size.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.animation).file_size.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.animation).file_size.le_ope(self.value()?),
+                Operator::In => ufh!(message.animation).file_size.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.animation).file_size.between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageAudio => Ok(message.audio.is_truthy()),
             Field::MessageAudioDuration => match self.operator()? {
                 Operator::Eq => ufh!(message.audio).duration.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.audio).duration.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.audio).duration.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.audio).duration.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.audio).duration.le_ope(self.value()?),
+                Operator::In => ufh!(message.audio).duration.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.audio).duration.between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageAudioPerformer => match self.operator()? {
                 Operator::Eq => ufh!(message.audio).performer.eq_ope(self.value()?),
-                Operator::Any => ufh!(message.audio).performer.any_ope(self.value()?),
-                Operator::All => ufh!(message.audio).performer.all_ope(self.value()?),
+                Operator::Any => ufh!(message.audio).performer.any_ope(self.keywords()?),
+                Operator::All => ufh!(message.audio).performer.all_ope(self.keywords()?),
                 Operator::Hd => ufh!(message.audio).performer.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.audio).performer.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageAudioMimeType => match self.operator()? {
                 Operator::Eq => ufh!(message.audio).mime_type.eq_ope(self.value()?),
                 Operator::In => ufh!(message.audio).mime_type.in_ope(self.value()?),
                 Operator::Hd => ufh!(message.audio).mime_type.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.audio).mime_type.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageAudioFileSize => match self.operator()? {
                 Operator::Eq => ufh!(message.audio).file_size.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.audio).file_size.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.audio).file_size.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.audio).file_size.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.audio).file_size.le_ope(self.value()?),
+                Operator::In => ufh!(message.audio).file_size.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.audio).file_size.between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageDocument => Ok(message.document.is_truthy()),
             Field::MessageDocumentFileName => match self.operator()? {
                 Operator::Eq => ufh!(message.document).file_name.eq_ope(self.value()?),
-                Operator::Any => ufh!(message.document).file_name.any_ope(self.value()?),
-                Operator::All => ufh!(message.document).file_name.all_ope(self.value()?),
+                Operator::Any => ufh!(message.document).file_name.any_ope(self.keywords()?),
+                Operator::All => ufh!(message.document).file_name.all_ope(self.keywords()?),
                 Operator::Hd => ufh!(message.document).file_name.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.document).file_name.td_ope(self.value()?),
+                Operator::Re => ufh!(message.document).file_name.re_ope(self.regex()?),
+                Operator::ReAny => ufh!(message.document).file_name.re_any_ope(self.regexes()),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageDocumentMimeType => match self.operator()? {
                 Operator::Eq => ufh!(message.document).mime_type.eq_ope(self.value()?),
                 Operator::In => ufh!(message.document).mime_type.in_ope(self.value()?),
                 Operator::Hd => ufh!(message.document).mime_type.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.document).mime_type.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageDocumentFileSize => match self.operator()? {
                 Operator::Eq => ufh!(message.document).file_size.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.document).file_size.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.document).file_size.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.document).file_size.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.document).file_size.le_ope(self.value()?),
+                Operator::In => ufh!(message.document).file_size.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.document).file_size.between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessagePhoto => Ok(message.photo.is_truthy()),
+            Field::MessagePhotoCount => self.match_integer(len_of(&message.photo)),
+            Field::MessagePhotoAnyWidth
+            | Field::MessagePhotoAllWidth
+            | Field::MessagePhotoAnyHeight
+            | Field::MessagePhotoAllHeight
+            | Field::MessagePhotoAnyFileSize
+            | Field::MessagePhotoAllFileSize => self.match_items(ctx),
             Field::MessageSticker => Ok(message.sticker.is_truthy()),
             Field::MessageStickerIsAnimated => {
                 Ok(child_is_truthy!(&message.sticker, is_animated).is_truthy())
@@ -758,114 +2648,165 @@ impl Cont {
             Field::MessageStickerEmoji => match self.operator()? {
                 Operator::Eq => ufh!(message.sticker).emoji.eq_ope(self.value()?),
                 Operator::In => ufh!(message.sticker).emoji.in_ope(self.value()?),
+                Operator::Td => ufh!(message.sticker).emoji.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageStickerSetName => match self.operator()? {
                 Operator::Eq => ufh!(message.sticker).set_name.eq_ope(self.value()?),
-                Operator::Any => ufh!(message.sticker).set_name.any_ope(self.value()?),
-                Operator::All => ufh!(message.sticker).set_name.all_ope(self.value()?),
+                Operator::Any => ufh!(message.sticker).set_name.any_ope(self.keywords()?),
+                Operator::All => ufh!(message.sticker).set_name.all_ope(self.keywords()?),
                 Operator::Hd => ufh!(message.sticker).set_name.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.sticker).set_name.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageVideo => Ok(message.video.is_truthy()),
             Field::MessageVideoDuration => match self.operator()? {
                 Operator::Eq => ufh!(message.video).duration.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.video).duration.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.video).duration.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.video).duration.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.video).duration.le_ope(self.value()?),
+                Operator::In => ufh!(message.video).duration.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.video).duration.between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageVideoMimeType => match self.operator()? {
                 Operator::Eq => ufh!(message.video).mime_type.eq_ope(self.value()?),
                 Operator::In => ufh!(message.video).mime_type.in_ope(self.value()?),
                 Operator::Hd => ufh!(message.video).mime_type.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.video).mime_type.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageVideoFileSize => match self.operator()? {
                 Operator::Eq => ufh!(message.video).file_size.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.video).file_size.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.video).file_size.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.video).file_size.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.video).file_size.le_ope(self.value()?),
+                Operator::In => ufh!(message.video).file_size.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.video).file_size.between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageVoice => Ok(message.voice.is_truthy()),
             Field::MessageVoiceDuration => match self.operator()? {
                 Operator::Eq => ufh!(message.voice).duration.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.voice).duration.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.voice).duration.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.voice).duration.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.voice).duration.le_ope(self.value()?),
+                Operator::In => ufh!(message.voice).duration.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.voice).duration.between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageVoiceMimeType => match self.operator()? {
                 Operator::Eq => ufh!(message.voice).mime_type.eq_ope(self.value()?),
                 Operator::In => ufh!(message.voice).mime_type.in_ope(self.value()?),
                 Operator::Hd => ufh!(message.voice).mime_type.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.voice).mime_type.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageVoiceFileSize => match self.operator()? {
                 Operator::Eq => ufh!(message.voice).file_size.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.voice).file_size.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.voice).file_size.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.voice).file_size.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.voice).file_size.le_ope(self.value()?),
+                Operator::In => ufh!(message.voice).file_size.in_ope(self.integers()?),
+                Operator::Between => ufh!(message.voice).file_size.between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageCaption => match self.operator()? {
                 Operator::Eq => message.caption.eq_ope(self.value()?),
                 Operator::In => message.caption.in_ope(self.value()?),
-                Operator::Any => message.caption.any_ope(self.value()?),
-                Operator::All => message.caption.all_ope(self.value()?),
+                Operator::Any => message.caption.any_ope(self.keywords()?),
+                Operator::All => message.caption.all_ope(self.keywords()?),
+                Operator::Hd => message.caption.hd_ope(self.value()?),
+                Operator::Td => message.caption.td_ope(self.value()?),
+                Operator::Re => message.caption.re_ope(self.regex()?),
+                Operator::ReAny => message.caption.re_any_ope(self.regexes()),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageCaptionLen => match self.operator()? {
                 Operator::Eq => message.caption.eq_ope_for_content_len(self.value()?),
                 Operator::Gt => message.caption.gt_ope_for_content_len(self.value()?),
+                Operator::Lt => message.caption.lt_ope_for_content_len(self.value()?),
                 Operator::Ge => message.caption.ge_ope_for_content_len(self.value()?),
                 Operator::Le => message.caption.le_ope_for_content_len(self.value()?),
+                Operator::In => message.caption.in_ope_for_content_len(self.integers()?),
+                Operator::Between => message.caption.between_ope_for_content_len(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
+            Field::MessageEntitiesUrl
+            | Field::MessageEntitiesMention
+            | Field::MessageEntitiesHashtag
+            | Field::MessageEntitiesTextLinkUrl
+            | Field::MessageUrlsHost
+            | Field::MessageUrlsDomain
+            | Field::MessageUrlsPath => self.match_contents(ctx),
+            Field::MessageEntitiesCount => self.match_integer(entity_count(message)),
+            Field::MessageEntitiesAnyType | Field::MessageEntitiesAllType => self.match_items(ctx),
             Field::MessageDice => Ok(message.dice.is_truthy()),
             Field::MessageDiceEmoji => match self.operator()? {
                 Operator::Eq => ufh!(message.dice).emoji.eq_ope(self.value()?),
                 Operator::In => ufh!(message.dice).emoji.in_ope(self.value()?),
+                Operator::Td => ufh!(message.dice).emoji.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessagePoll => Ok(message.poll.is_truthy()),
             Field::MessagePollType => match self.operator()? {
                 Operator::Eq => ufh!(message.poll).type_.eq_ope(self.value()?),
                 Operator::In => ufh!(message.poll).type_.in_ope(self.value()?),
+                Operator::Td => ufh!(message.poll).type_.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageVenue => Ok(message.venue.is_truthy()),
             Field::MessageVenueTitle => match self.operator()? {
                 Operator::Eq => ufh!(message.venue).title.eq_ope(self.value()?),
-                Operator::Any => ufh!(message.venue).title.any_ope(self.value()?),
-                Operator::All => ufh!(message.venue).title.all_ope(self.value()?),
+                Operator::Any => ufh!(message.venue).title.any_ope(self.keywords()?),
+                Operator::All => ufh!(message.venue).title.all_ope(self.keywords()?),
                 Operator::Hd => ufh!(message.venue).title.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.venue).title.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageVenueAddress => match self.operator()? {
                 Operator::Eq => ufh!(message.venue).address.eq_ope(self.value()?),
-                Operator::Any => ufh!(message.venue).address.any_ope(self.value()?),
-                Operator::All => ufh!(message.venue).address.all_ope(self.value()?),
+                Operator::Any => ufh!(message.venue).address.any_ope(self.keywords()?),
+                Operator::All => ufh!(message.venue).address.all_ope(self.keywords()?),
                 Operator::Hd => ufh!(message.venue).address.hd_ope(self.value()?),
+                Operator::Td => ufh!(message.venue).address.td_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageLocation => Ok(message.location.is_truthy()),
             Field::MessageLocationLongitude => match self.operator()? {
                 Operator::Eq => ufh!(message.location).longitude.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.location).longitude.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.location).longitude.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.location).longitude.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.location).longitude.le_ope(self.value()?),
+                Operator::In => ufh!(message.location).longitude.in_ope(self.value()?),
+                Operator::Between => ufh!(message.location).longitude.between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageLocationLatitude => match self.operator()? {
                 Operator::Eq => ufh!(message.location).latitude.eq_ope(self.value()?),
                 Operator::Gt => ufh!(message.location).latitude.gt_ope(self.value()?),
+                Operator::Lt => ufh!(message.location).latitude.lt_ope(self.value()?),
                 Operator::Ge => ufh!(message.location).latitude.ge_ope(self.value()?),
                 Operator::Le => ufh!(message.location).latitude.le_ope(self.value()?),
+                Operator::In => ufh!(message.location).latitude.in_ope(self.value()?),
+                Operator::Between => ufh!(message.location).latitude.between_ope(self.value()?),
                 _ => Err(unsupported_operator_err()?),
             },
             Field::MessageNewChatMembers => Ok(message.new_chat_members.is_truthy()),
+            Field::MessageNewChatMembersCount => {
+                self.match_integer(len_of(&message.new_chat_members))
+            }
+            Field::MessageNewChatMembersAnyId
+            | Field::MessageNewChatMembersAllId
+            | Field::MessageNewChatMembersAnyIsBot
+            | Field::MessageNewChatMembersAllIsBot
+            | Field::MessageNewChatMembersAnyFullName
+            | Field::MessageNewChatMembersAllFullName => self.match_items(ctx),
             Field::MessageLeftChatMember => Ok(message.left_chat_member.is_truthy()),
             Field::MessageNewChatTitle => Ok(message.new_chat_title.is_truthy()),
             Field::MessageNewChatPhoto => Ok(message.new_chat_photo.is_truthy()),
@@ -889,26 +2830,14 @@ impl Cont {
             } else {
                 Ok(false)
             }
+            Field::MessageIsForwarded => Ok(
+                message.forward_from.is_truthy() || // 转发自用户
+                message.forward_from_chat.is_truthy() || // 转发自频道
+                message.forward_sender_name.is_truthy() || // 转发自隐藏了账号的用户
+                message.forward_date.is_truthy() // 转发时间
+            ),
             //
             // field => Err(Error::FieldNotEndabled { field }),
-        };
-
-        match r {
-            Ok(no_negative) => {
-                if self.is_negative {
-                    Ok(!no_negative)
-                } else {
-                    Ok(no_negative)
-                }
-            }
-            Err(Error::FalsyValueHosting) => {
-                if self.is_negative {
-                    Ok(true)
-                } else {
-                    Ok(false)
-                }
-            }
-            e => e,
         }
     }
 }
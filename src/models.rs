@@ -1,20 +1,29 @@
 //! All types used in a Bot API message.
 
 use serde::{Deserialize, Serialize};
-use std::rc::Rc;
+use std::convert::TryFrom;
+use std::sync::Arc;
 
 /// This object represents a message.
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Message {
     /// Sender, empty for messages sent to channels.
     pub from: Option<User>,
+    /// Sender of the message, sent on behalf of a chat. The channel itself for channel messages.
+    /// The supergroup itself for messages from anonymous group administrators.
+    /// The linked channel for messages automatically forwarded to the discussion group.
+    pub sender_chat: Option<Chat>,
     /// For forwarded messages, sender of the original message.
     pub forward_from: Option<User>,
     /// For messages forwarded from channels, information about the original channel.
     pub forward_from_chat: Option<Chat>,
+    /// Sender's name for messages forwarded from users who disallow adding a link to their account in forwarded messages.
+    pub forward_sender_name: Option<String>,
+    /// For forwarded messages, date the original message was sent in Unix time.
+    pub forward_date: Option<i64>,
     /// For replies, the original message.
     /// Note that the Message object in this field will not contain further `reply_to_message` fields even if it itself is a reply.
-    pub reply_to_message: Option<Rc<Message>>,
+    pub reply_to_message: Option<Arc<Message>>,
     /// Bot through which the message was sent.
     pub via_bot: Option<User>,
     /// For text messages, the actual UTF-8 text of the message, 0-4096 characters.
@@ -62,7 +71,7 @@ pub struct Message {
     pub new_chat_photo: Option<Vec<PhotoSize>>,
     /// Specified message was pinned. Note that the Message object in this field will
     /// not contain further `reply_to_message` fields even if it is itself a reply.
-    pub pinned_message: Option<Rc<Message>>,
+    pub pinned_message: Option<Arc<Message>>,
 }
 
 /// This object represents a Telegram user or bot.
@@ -229,6 +238,25 @@ pub struct Location {
     pub latitude: f64,
 }
 
+impl MessageEntity {
+    /// Text of the entity, sliced from the text or caption it belongs to by its UTF-16 offset and length.
+    ///
+    /// Returns `None` if the entity is out of bounds or splits a surrogate pair.
+    pub fn text(&self, source: &str) -> Option<String> {
+        self.text_from_utf16(&source.encode_utf16().collect::<Vec<_>>())
+    }
+
+    /// Text of the entity, sliced from the UTF-16 code units of the text or caption it belongs to.
+    ///
+    /// Prefer this over [`text`](#method.text) when slicing many entities from the same source.
+    pub fn text_from_utf16(&self, units: &[u16]) -> Option<String> {
+        let begin = usize::try_from(self.offset).ok()?;
+        let end = begin.checked_add(usize::try_from(self.length).ok()?)?;
+
+        String::from_utf16(units.get(begin..end)?).ok()
+    }
+}
+
 impl User {
     pub fn full_name(&self) -> String {
         let mut full_name = self.first_name.clone();
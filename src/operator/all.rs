@@ -1,6 +1,7 @@
 /// 运算符 `all` 的 trait 和相关实现。
-use crate::matches::{GetSingleValue, Values};
+use crate::matches::{GetSingleValue, Keywords, Values};
 use crate::result::Result;
+use aho_corasick::AhoCorasick;
 
 pub trait AllOperator<T> {
     fn all_ope(&self, target: T) -> Result<bool>;
@@ -28,3 +29,52 @@ impl AllOperator<&Values> for Option<String> {
         }
     }
 }
+
+// 自动机中的模式不重复，找到的不同模式数量等于模式总数即表示包含全部。
+impl AllOperator<&AhoCorasick> for String {
+    fn all_ope(&self, target: &AhoCorasick) -> Result<bool> {
+        let mut found = vec![false; target.patterns_len()];
+        let mut remaining = found.len();
+
+        for m in target.find_overlapping_iter(self.as_str()) {
+            if remaining == 0 {
+                break;
+            }
+
+            let found = &mut found[m.pattern().as_usize()];
+            if !*found {
+                *found = true;
+                remaining -= 1;
+            }
+        }
+
+        Ok(remaining == 0)
+    }
+}
+impl AllOperator<&AhoCorasick> for Option<String> {
+    fn all_ope(&self, target: &AhoCorasick) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.all_ope(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl AllOperator<Keywords<'_>> for String {
+    fn all_ope(&self, target: Keywords<'_>) -> Result<bool> {
+        match target {
+            Keywords::Values(values) => self.all_ope(values),
+            Keywords::Automaton(automaton) => self.all_ope(automaton),
+        }
+    }
+}
+impl AllOperator<Keywords<'_>> for Option<String> {
+    fn all_ope(&self, target: Keywords<'_>) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.all_ope(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
@@ -1,6 +1,7 @@
 /// 运算符 `any` 的 trait 和相关实现。
-use crate::matches::{GetSingleValue, Values};
+use crate::matches::{GetSingleValue, Keywords, Values};
 use crate::result::Result;
+use aho_corasick::AhoCorasick;
 
 pub trait AnyOperator<T> {
     fn any_ope(&self, target: T) -> Result<bool>;
@@ -28,3 +29,36 @@ impl AnyOperator<&Values> for Option<String> {
         }
     }
 }
+
+impl AnyOperator<&AhoCorasick> for String {
+    fn any_ope(&self, target: &AhoCorasick) -> Result<bool> {
+        Ok(target.is_match(self))
+    }
+}
+impl AnyOperator<&AhoCorasick> for Option<String> {
+    fn any_ope(&self, target: &AhoCorasick) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.any_ope(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl AnyOperator<Keywords<'_>> for String {
+    fn any_ope(&self, target: Keywords<'_>) -> Result<bool> {
+        match target {
+            Keywords::Values(values) => self.any_ope(values),
+            Keywords::Automaton(automaton) => self.any_ope(automaton),
+        }
+    }
+}
+impl AnyOperator<Keywords<'_>> for Option<String> {
+    fn any_ope(&self, target: Keywords<'_>) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.any_ope(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
@@ -0,0 +1,56 @@
+/// 运算符 `between` 的 trait 和相关实现。
+use crate::matches::{GetSingleValue, Values};
+use crate::result::Result;
+
+pub trait BetweenOperator<T> {
+    fn between_ope(&self, target: T) -> Result<bool>;
+}
+pub trait BetweenOperatorForContentLen<T> {
+    fn between_ope_for_content_len(&self, target: T) -> Result<bool>;
+}
+
+impl BetweenOperator<&Values> for i64 {
+    fn between_ope(&self, target: &Values) -> Result<bool> {
+        target.get_a_range()?.contains_integer(*self)
+    }
+}
+
+impl BetweenOperator<&Values> for i32 {
+    fn between_ope(&self, target: &Values) -> Result<bool> {
+        (*self as i64).between_ope(target)
+    }
+}
+
+impl BetweenOperator<&Values> for Option<i32> {
+    fn between_ope(&self, target: &Values) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.between_ope(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl BetweenOperator<&Values> for f64 {
+    fn between_ope(&self, target: &Values) -> Result<bool> {
+        target.get_a_range()?.contains_decimal(*self)
+    }
+}
+
+impl BetweenOperatorForContentLen<&Values> for String {
+    fn between_ope_for_content_len(&self, target: &Values) -> Result<bool> {
+        let self_len = self.chars().collect::<Vec<_>>().len() as i64;
+
+        target.get_a_range()?.contains_integer(self_len)
+    }
+}
+
+impl BetweenOperatorForContentLen<&Values> for Option<String> {
+    fn between_ope_for_content_len(&self, target: &Values) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.between_ope_for_content_len(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
@@ -0,0 +1,23 @@
+/// 运算符 `domain_in` 的 trait 和相关实现。
+use crate::matches::DomainSet;
+use crate::result::Result;
+
+pub trait DomainInOperator<T> {
+    fn domain_in_ope(&self, target: T) -> Result<bool>;
+}
+
+impl DomainInOperator<&DomainSet> for String {
+    fn domain_in_ope(&self, target: &DomainSet) -> Result<bool> {
+        Ok(target.contains(self))
+    }
+}
+
+impl DomainInOperator<&DomainSet> for Option<String> {
+    fn domain_in_ope(&self, target: &DomainSet) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.domain_in_ope(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
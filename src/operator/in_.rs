@@ -1,10 +1,13 @@
 /// 运算符 `in` 的 trait 和相关实现。
-use crate::matches::{GetSingleValue, Values};
+use crate::matches::{GetSingleValue, IntegerSet, Integers, Values};
 use crate::result::Result;
 
 pub trait InOperator<T> {
     fn in_ope(&self, target: T) -> Result<bool>;
 }
+pub trait InOperatorForContentLen<T> {
+    fn in_ope_for_content_len(&self, target: T) -> Result<bool>;
+}
 
 impl InOperator<&Values> for String {
     fn in_ope(&self, target: &Values) -> Result<bool> {
@@ -30,3 +33,78 @@ impl InOperator<&Values> for Option<String> {
         }
     }
 }
+
+// 数字的值列表中可以包含范围。
+
+impl InOperator<&Values> for i64 {
+    fn in_ope(&self, target: &Values) -> Result<bool> {
+        for v in target {
+            if v.contains_integer(*self)? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+impl InOperator<&IntegerSet> for i64 {
+    fn in_ope(&self, target: &IntegerSet) -> Result<bool> {
+        target.contains(*self)
+    }
+}
+
+impl InOperator<Integers<'_>> for i64 {
+    fn in_ope(&self, target: Integers<'_>) -> Result<bool> {
+        match target {
+            Integers::Values(values) => self.in_ope(values),
+            Integers::Set(set) => self.in_ope(set),
+        }
+    }
+}
+
+impl InOperator<Integers<'_>> for i32 {
+    fn in_ope(&self, target: Integers<'_>) -> Result<bool> {
+        (*self as i64).in_ope(target)
+    }
+}
+
+impl InOperator<Integers<'_>> for Option<i32> {
+    fn in_ope(&self, target: Integers<'_>) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.in_ope(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl InOperator<&Values> for f64 {
+    fn in_ope(&self, target: &Values) -> Result<bool> {
+        for v in target {
+            if v.contains_decimal(*self)? {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+impl InOperatorForContentLen<Integers<'_>> for String {
+    fn in_ope_for_content_len(&self, target: Integers<'_>) -> Result<bool> {
+        let self_len = self.chars().collect::<Vec<_>>().len() as i64;
+
+        self_len.in_ope(target)
+    }
+}
+
+impl InOperatorForContentLen<Integers<'_>> for Option<String> {
+    fn in_ope_for_content_len(&self, target: Integers<'_>) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.in_ope_for_content_len(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
@@ -0,0 +1,56 @@
+/// 运算符 `lt` 的 trait 和相关实现。
+use crate::matches::{GetSingleValue, Values};
+use crate::result::Result;
+
+pub trait LtOperator<T> {
+    fn lt_ope(&self, target: T) -> Result<bool>;
+}
+pub trait LtOperatorForContentLen<T> {
+    fn lt_ope_for_content_len(&self, target: T) -> Result<bool>;
+}
+
+impl LtOperator<&Values> for i64 {
+    fn lt_ope(&self, target: &Values) -> Result<bool> {
+        Ok(*self < target.get_an_integer()?)
+    }
+}
+
+impl LtOperator<&Values> for i32 {
+    fn lt_ope(&self, target: &Values) -> Result<bool> {
+        (*self as i64).lt_ope(target)
+    }
+}
+
+impl LtOperator<&Values> for Option<i32> {
+    fn lt_ope(&self, target: &Values) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.lt_ope(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl LtOperator<&Values> for f64 {
+    fn lt_ope(&self, target: &Values) -> Result<bool> {
+        Ok(*self < target.get_a_decimal()?)
+    }
+}
+
+impl LtOperatorForContentLen<&Values> for String {
+    fn lt_ope_for_content_len(&self, target: &Values) -> Result<bool> {
+        let self_len = self.chars().collect::<Vec<_>>().len() as i64;
+
+        Ok(self_len < target.get_an_integer()?)
+    }
+}
+
+impl LtOperatorForContentLen<&Values> for Option<String> {
+    fn lt_ope_for_content_len(&self, target: &Values) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.lt_ope_for_content_len(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
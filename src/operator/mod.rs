@@ -1,18 +1,23 @@
-use strum_macros::{EnumString, ToString};
+use strum_macros::{Display, EnumIter, EnumString, ToString};
+use unicode_normalization::UnicodeNormalization;
 
 pub mod all;
 pub mod any;
+pub mod between;
+pub mod domain_in;
 pub mod eq;
 pub mod ge;
 pub mod gt;
 pub mod hd;
 pub mod in_;
 pub mod le;
+pub mod lt;
 pub mod prelude;
+pub mod re;
 pub mod td;
 
 /// 运算符。
-#[derive(Debug, Eq, PartialEq, Copy, Clone, EnumString, ToString)]
+#[derive(Debug, Eq, PartialEq, Hash, Copy, Clone, EnumString, EnumIter, ToString)]
 #[strum(serialize_all = "snake_case")]
 pub enum Operator {
     /// 等于。
@@ -35,4 +40,51 @@ pub enum Operator {
     Hd,
     // 尾部相等。
     Td,
+    /// 匹配正则表达式。
+    Re,
+    /// 匹配任意一个正则表达式。
+    ReAny,
+    /// 位于范围之内（包含两端）。
+    Between,
+    /// 属于域名列表，支持通配子域名。
+    DomainIn,
+}
+
+impl Operator {
+    /// 运算符的值是否为列表。
+    pub fn takes_list(&self) -> bool {
+        matches!(
+            self,
+            Operator::In | Operator::Any | Operator::All | Operator::ReAny | Operator::DomainIn
+        )
+    }
+}
+
+/// 运算符修饰符。
+///
+/// 修饰符附加在运算符之后，以 `.` 分隔。例如 `any.i`、`eq.nfkc` 或 `hd.i.nfkc`。
+/// 修饰符会同时作用于字段内容和条件中的值。
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Copy, Clone, EnumString, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Modifier {
+    /// NFKC 规范化（包括全角转半角）。
+    Nfkc,
+    /// 忽略大小写。
+    I,
+}
+
+/// 使用修饰符规范化文本。
+///
+/// 先执行 NFKC 规范化，再转换为小写。
+pub fn normalize(text: &str, modifiers: &[Modifier]) -> String {
+    let mut text = text.to_owned();
+
+    if modifiers.contains(&Modifier::Nfkc) {
+        text = text.nfkc().collect();
+    }
+    if modifiers.contains(&Modifier::I) {
+        text = text.to_lowercase();
+    }
+
+    text
 }
@@ -1,11 +1,15 @@
 pub use super::{
     all::AllOperator,
     any::AnyOperator,
+    between::{BetweenOperator, BetweenOperatorForContentLen},
+    domain_in::DomainInOperator,
     eq::{EqOperator, EqOperatorForContentLen},
     ge::{GeOperator, GeOperatorForContentLen},
     gt::{GtOperator, GtOperatorForContentLen},
     hd::HdOperator,
-    in_::InOperator,
+    in_::{InOperator, InOperatorForContentLen},
     le::{LeOperator, LeOperatorForContentLen},
+    lt::{LtOperator, LtOperatorForContentLen},
+    re::{ReAnyOperator, ReOperator},
     td::TdOperator,
 };
@@ -0,0 +1,43 @@
+/// 运算符 `re` 和 `re_any` 的 trait 和相关实现。
+use crate::result::Result;
+use regex::Regex;
+
+pub trait ReOperator<T> {
+    fn re_ope(&self, target: T) -> Result<bool>;
+}
+
+pub trait ReAnyOperator<T> {
+    fn re_any_ope(&self, target: T) -> Result<bool>;
+}
+
+impl ReOperator<&Regex> for String {
+    fn re_ope(&self, target: &Regex) -> Result<bool> {
+        Ok(target.is_match(self))
+    }
+}
+
+impl ReOperator<&Regex> for Option<String> {
+    fn re_ope(&self, target: &Regex) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.re_ope(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl ReAnyOperator<&[Regex]> for String {
+    fn re_any_ope(&self, target: &[Regex]) -> Result<bool> {
+        Ok(target.iter().any(|re| re.is_match(self)))
+    }
+}
+
+impl ReAnyOperator<&[Regex]> for Option<String> {
+    fn re_any_ope(&self, target: &[Regex]) -> Result<bool> {
+        if let Some(self_data) = self {
+            self_data.re_any_ope(target)
+        } else {
+            Ok(false)
+        }
+    }
+}
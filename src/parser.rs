@@ -2,17 +2,22 @@
 //!
 //! 产生式：
 //! ```text
-//! 规则 -> 条件组 可选条件组列表 <EOF>
-//! 条件组 -> <(> 条件 可选条件列表 <)>
+//! 规则 -> 表达式 <EOF>
+//! 表达式 -> 与表达式 可选与表达式列表
+//! 与表达式 -> 一元表达式 可选一元表达式列表
+//! 一元表达式 -> <not> 一元表达式 | 基本表达式
+//! 基本表达式 -> <(> 表达式 <)> | 条件
 //! 条件 -> 未取反条件 | <not> 未取反条件
-//! 未取反条件 -> <字段> <运算符> 值表示
-//! 值表示 -> 单值表示 | 多值表示
+//! 未取反条件 -> <字段> | <字段> <运算符> 值表示
+//! 值表示 -> 单值表示 | 多值表示 | <list>
 //! 多值表示 -> <{> 单值表示 单值表示 ... <}>
-//! 单值表示 -> <"> <letter> <"> | <integer> | <decimal>
-//! 可选条件列表 -> <and> 条件 可选条件列表 | <空>
-//! 可选条件组列表 -> <or> 条件组 可选条件组列表 | <空>
+//! 单值表示 -> <"> <letter> <"> | <integer> | <decimal> | <boolean> | <range>
+//! 可选一元表达式列表 -> <and> 一元表达式 可选一元表达式列表 | <空>
+//! 可选与表达式列表 -> <or> 与表达式 可选与表达式列表 | <空>
 //! ```
 //!
+//! 运算的优先级由高到低依次为 `not`、`and`、`or`，可使用括号任意嵌套以改变优先级。
+//!
 //! 当前的实现基于递归下降算法，语法制导直接生成 [`Matcher`](../matcher/struct.Matcher.html) 对象。
 //!
 //! 一个使用案例：
@@ -27,7 +32,7 @@
 //! let input = rule.chars().collect::<Vec<_>>();
 //! let mut lexer = Lexer::new(&input);
 //! let parser = Parser::new(&mut lexer)?;
-//! let mut matcher = parser.parse()?;
+//! let matcher = parser.parse()?;
 //! // 两条典型的东南亚博彩招人消息。
 //! let message_text1 = format!("柬埔寨菠菜需要的来");
 //! let message_text2 = format!("东南亚博彩招聘");
@@ -55,10 +60,12 @@
 
 use super::error::Error;
 use super::lexer::{Lexer, Position, Token};
-use super::matches::{Cont, ContGroups, Matcher, Value};
+use super::matches::{Cont, Expr, Matcher, Range, Value};
+use super::operator::Operator;
 use super::result::Result;
 
 use derivative::Derivative;
+use std::str::FromStr;
 
 type Input = Vec<Token>;
 
@@ -78,13 +85,17 @@ pub struct Parser<'a> {
     pos: usize,
     // 当前的 token（current token）。
     pub ct: Option<&'a Token>,
+    // 恢复模式下收集的错误，`None` 表示遇到错误立即返回。
+    errors: Option<Vec<Error>>,
+    // 词法分析出错的位置。
+    lexical_failures: &'a [usize],
 }
 
 impl<'a> Parser<'a> {
     /// 从词法分析器创建一个解析器。
     pub fn new(lexer: &'a mut Lexer<'a>) -> Result<Self> {
-        // 确保已完成分析。
-        if !lexer.is_end() {
+        // 确保已完成分析。空的输入同样需要分析出 `EOF`。
+        if !lexer.is_end() || lexer.output().is_empty() {
             lexer.tokenize()?;
         }
 
@@ -96,94 +107,195 @@ impl<'a> Parser<'a> {
             positions: lexer.positions(),
             pos: 0,
             ct: input.get(0),
+            errors: None,
+            lexical_failures: lexer.failures(),
         })
     }
 
     /// 解析并得到匹配器对象。
     pub fn parse(mut self) -> Result<Matcher> {
-        let mut groups: ContGroups = vec![];
-
-        groups.push(self.parse_group()?);
-
-        self.scan();
-        let mut optinal_groups = self.parse_optinal_group_list(vec![])?;
-        if optinal_groups.len() > 0 {
-            groups.append(&mut optinal_groups);
-        }
+        self.check_empty()?;
+        let expr = self.parse_expr()?;
 
         self.scan();
         if self.ct != Some(&Token::EOF) {
             let position = self.current_position()?;
             return Err(Error::ShouldEndHere {
-                column: position.begin,
+                position: *position,
             });
         }
 
-        Ok(Matcher::new(groups))
+        Ok(Matcher::from_expr(expr))
     }
 
-    fn parse_group(&mut self) -> Result<Vec<Cont>> {
-        if self.ct != Some(&Token::OpenParenthesis) {
-            let position = self.current_position()?;
-            return Err(Error::ShouldOpenParenthesisHere {
-                column: position.begin,
-            });
+    /// 以恢复模式解析，返回匹配器对象或全部的错误。
+    ///
+    /// 出错的条件会被跳过，解析从同一层级的下一个 `or` 或 `)` 处继续。配合
+    /// [`Lexer::tokenize_recovering`](../lexer/struct.Lexer.html#method.tokenize_recovering) 使用时，
+    /// 由词法错误引起的语法错误不会被重复报告。
+    pub fn parse_recovering(mut self) -> std::result::Result<Matcher, Vec<Error>> {
+        if let Err(e) = self.check_empty() {
+            return Err(vec![e]);
         }
+        self.errors = Some(vec![]);
 
-        let mut conts = vec![];
+        let result = self.parse_expr().and_then(|expr| {
+            self.scan();
+            if self.ct != Some(&Token::EOF) {
+                let position = *self.current_position()?;
+                if !self.lexical_failures.iter().any(|f| *f >= position.begin) {
+                    return Err(Error::ShouldEndHere { position });
+                }
+            }
 
-        self.scan();
-        conts.push(self.parse_cont()?);
+            Ok(expr)
+        });
+        let mut errors = self.errors.take().unwrap_or_default();
 
-        self.scan();
+        match result {
+            Ok(expr) if errors.is_empty() => Ok(Matcher::from_expr(expr)),
+            Ok(_) => Err(errors),
+            Err(e) => {
+                errors.push(e);
 
-        let mut optinal_conts = self.parse_optinal_cont_list(vec![])?;
-        if optinal_conts.len() > 0 {
-            conts.append(&mut optinal_conts);
+                Err(errors)
+            }
         }
-        self.scan();
+    }
 
-        if self.ct != Some(&Token::CloseParenthesis) {
-            let position = self.current_position()?;
+    // 解析具有 `or` 关系的表达式。
+    fn parse_expr(&mut self) -> Result<Expr> {
+        let mut exprs = vec![self.parse_and_expr()?];
 
-            return Err(Error::ShouldCloseParenthesisHere {
-                column: position.begin,
-            });
+        while self.scan() == Some(&Token::Or) {
+            self.scan();
+            exprs.push(self.parse_and_expr()?);
         }
+        self.back();
 
-        Ok(conts)
+        if exprs.len() == 1 {
+            Ok(exprs.remove(0))
+        } else {
+            Ok(Expr::Or(exprs))
+        }
     }
 
-    fn parse_optinal_cont_list(&mut self, mut conts: Vec<Cont>) -> Result<Vec<Cont>> {
-        if conts.len() > 0 {
+    // 解析具有 `and` 关系的表达式。
+    fn parse_and_expr(&mut self) -> Result<Expr> {
+        let mut exprs = vec![];
+
+        loop {
+            let start = self.pos;
+            match self.parse_unary_expr() {
+                Ok(expr) => exprs.push(expr),
+                Err(e) => self.recover(e, start)?,
+            }
+
+            if self.scan() != Some(&Token::And) {
+                break;
+            }
             self.scan();
         }
+        self.back();
 
-        if self.ct != Some(&Token::And) {
-            self.back();
-            return Ok(conts);
+        if exprs.len() == 1 {
+            Ok(exprs.remove(0))
+        } else {
+            // 恢复模式下出错的表达式被丢弃，可能产生空的 `and` 表达式，它不会被使用。
+            Ok(Expr::And(exprs))
         }
+    }
 
-        self.scan();
-        conts.push(self.parse_cont()?);
+    // 规则为空（或只有空白和注释）时返回错误。
+    fn check_empty(&self) -> Result<()> {
+        if self.ct == Some(&Token::EOF) {
+            return Err(Error::MissingCondition {
+                position: *self.current_position()?,
+            });
+        }
 
-        self.parse_optinal_cont_list(conts)
+        Ok(())
     }
 
-    fn parse_optinal_group_list(&mut self, mut groups: ContGroups) -> Result<ContGroups> {
-        if groups.len() > 0 {
+    // 在恢复模式下记录错误，否则直接返回错误。
+    //
+    // 条件的编译错误（附带位置的错误）产生时条件已被完整解析，无需跳过。语法错误则从出错的表达式的开头
+    // 跳过到同一层级的 `or`、`)` 或结束之前，跳过的范围中有词法错误时，语法错误被视为词法错误的后果而不再报告。
+    fn recover(&mut self, error: Error, start: usize) -> Result<()> {
+        if self.errors.is_none() {
+            return Err(error);
+        }
+
+        if !matches!(error, Error::Located { .. }) {
+            let begin = self.at_position(start)?.begin;
+
+            self.scan_at(start);
+            let mut depth = 0;
+            loop {
+                match self.ct {
+                    None | Some(Token::EOF) => break,
+                    Some(Token::OpenParenthesis) => depth += 1,
+                    Some(Token::CloseParenthesis) if depth > 0 => depth -= 1,
+                    Some(Token::CloseParenthesis) | Some(Token::Or) if depth == 0 => break,
+                    _ => (),
+                }
+                self.scan();
+            }
+            let end = self.current_position()?.begin;
+
+            // 停在同步位置之前，交由调用者继续处理。位于开头时直接跳过。
+            if self.pos > 0 {
+                self.back();
+            }
+
+            if self
+                .lexical_failures
+                .iter()
+                .any(|f| (begin..=end).contains(f))
+            {
+                return Ok(());
+            }
+        }
+
+        if let Some(errors) = &mut self.errors {
+            errors.push(error);
+        }
+
+        Ok(())
+    }
+
+    // 解析可能被取反的表达式。
+    fn parse_unary_expr(&mut self) -> Result<Expr> {
+        // 紧跟字段的 `not` 属于条件自身，由条件解析处理。
+        if self.ct == Some(&Token::Not) && self.input.get(self.pos + 1) != Some(&Token::Field) {
             self.scan();
+            let expr = self.parse_unary_expr()?;
+
+            return Ok(Expr::Not(Box::new(expr)));
         }
 
-        if self.ct != Some(&Token::Or) {
-            self.back();
-            return Ok(groups);
+        self.parse_primary_expr()
+    }
+
+    // 解析括号包裹的表达式或单个条件。
+    fn parse_primary_expr(&mut self) -> Result<Expr> {
+        if self.ct != Some(&Token::OpenParenthesis) {
+            return self.parse_cont().map(Expr::Cont);
         }
 
         self.scan();
-        groups.push(self.parse_group()?);
+        let expr = self.parse_expr()?;
 
-        self.parse_optinal_group_list(groups)
+        self.scan();
+        if self.ct != Some(&Token::CloseParenthesis) {
+            let position = self.current_position()?;
+
+            return Err(Error::ShouldCloseParenthesisHere {
+                position: *position,
+            });
+        }
+
+        Ok(expr)
     }
 
     fn parse_cont(&mut self) -> Result<Cont> {
@@ -198,35 +310,81 @@ impl<'a> Parser<'a> {
         if self.ct != Some(&Token::Field) {
             let position = self.current_position()?;
             return Err(Error::MissingField {
-                column: position.begin,
+                position: *position,
             });
         }
         let field = self.current_data()?.iter().collect();
+        let field_span = *self.current_position()?;
 
         self.scan();
 
-        if self.ct == Some(&Token::And) || self.ct == Some(&Token::CloseParenthesis) {
+        if self.ct == Some(&Token::And)
+            || self.ct == Some(&Token::Or)
+            || self.ct == Some(&Token::CloseParenthesis)
+            || self.ct == Some(&Token::EOF)
+        {
             self.back();
             // 单字段条件
-            Ok(Cont::single_field(is_negative, field)?)
+            Cont::single_field(is_negative, field).map_err(|e| locate(e, field_span))
         } else {
             // 多字段条件
             if self.ct != Some(&Token::Operator) {
                 let position = self.current_position()?;
                 return Err(Error::MissingOperator {
-                    column: position.begin,
+                    position: *position,
                 });
             }
-            let operator = self.current_data()?.iter().collect();
+            let operator: String = self.current_data()?.iter().collect();
+            let operator_span = *self.current_position()?;
 
             self.scan();
-            let value = self.parse_value()?;
-
-            Ok(Cont::new(is_negative, field, operator, value)?)
+            let value_position = *self.current_position()?;
+            let is_list = self.ct == Some(&Token::OpenBrace);
+            let value = self.parse_value().map_err(|e| match e {
+                // 运算符之后直接缺失值，记录运算符以便按照它接受单个值还是列表给出提示。
+                Error::ShouldValueHere { position } if !is_list => Error::MissingValue {
+                    position,
+                    operator: operator_of(&operator),
+                },
+                e => e,
+            })?;
+            let value_span = Position {
+                end: self.current_position()?.end,
+                ..value_position
+            };
+
+            let cont = Cont::new(is_negative, field, operator, value).map_err(|e| {
+                // 根据错误的类别定位到字段、运算符或值。
+                let span = match e {
+                    Error::UnknownField { .. } | Error::FieldNotEndabled { .. } => field_span,
+                    Error::UnknownOperator { .. }
+                    | Error::UnknownModifier { .. }
+                    | Error::UnsupportedOperator { .. }
+                    | Error::UnsupportedModifier { .. } => operator_span,
+                    _ => value_span,
+                };
+
+                locate(e, span)
+            })?;
+
+            // 单值运算符的值不能写成列表，即使列表中只有一个值。
+            match cont.operator {
+                Some(operator) if is_list && !operator.takes_list() => {
+                    Err(locate(Error::ExpectedSingleValue { operator }, value_span))
+                }
+                _ => Ok(cont),
+            }
         }
     }
 
     fn parse_value(&mut self) -> Result<Vec<Value>> {
+        // 匹配命名列表的引用，去掉开头的 `$`。
+        if self.ct == Some(&Token::List) {
+            let name = self.current_data()?[1..].iter().collect();
+
+            return Ok(vec![Value::List { name }]);
+        }
+
         // 匹配多值
         if self.ct == Some(&Token::OpenBrace) {
             let mut value = vec![];
@@ -248,30 +406,31 @@ impl<'a> Parser<'a> {
     fn prase_single_value(&mut self) -> Result<Value> {
         let position = self.current_position()?;
 
-        // 转换整数
-        if self.ct == Some(&Token::Integer) {
+        // 转换整数或小数。
+        if self.ct == Some(&Token::Integer) || self.ct == Some(&Token::Decimal) {
             let value_data = self.at_data(self.pos)?;
-            let value_string = value_data.iter().collect::<String>();
-            let value_integer =
-                i64::from_str_radix(&value_string, 10).map_err(|_| Error::IntegerParseFailed {
-                    column: position.begin,
-                })?;
 
-            return Ok(Value::Integer(value_integer));
+            return parse_number(value_data, *position);
         }
 
-        // 转换小数。
-        if self.ct == Some(&Token::Decimal) {
+        // 转换范围，两端各自作为整数或小数。
+        if self.ct == Some(&Token::Range) {
             let value_data = self.at_data(self.pos)?;
-            let string_value = value_data.iter().collect::<String>();
-            let decimal_value =
-                string_value
-                    .parse::<f64>()
-                    .map_err(|_| Error::DecimalParseFailed {
-                        column: position.begin,
-                    })?;
-
-            return Ok(Value::Decimal(decimal_value));
+            let separator = value_data.windows(2).position(|w| w == ['.', '.']).ok_or(
+                Error::ShouldValueHere {
+                    position: *position,
+                },
+            )?;
+            let start = parse_number(&value_data[..separator], position.offset(0))?;
+            let end = parse_number(&value_data[separator + 2..], position.offset(separator + 2))?;
+
+            return Ok(Value::Range(Box::new(Range { start, end })));
+        }
+
+        if self.ct == Some(&Token::Boolean) {
+            let value_data = self.at_data(self.pos)?;
+
+            return Ok(Value::Boolean(value_data == ['t', 'r', 'u', 'e']));
         }
 
         if self.ct == Some(&Token::Quote)
@@ -279,14 +438,15 @@ impl<'a> Parser<'a> {
             && self.input.get(self.pos + 2) == Some(&Token::Quote)
         {
             let value_data = self.at_data(self.pos + 1)?;
+            let letter = unescape(value_data, *self.at_position(self.pos + 1)?)?;
 
             self.scan_at(self.pos + 2);
 
-            return Ok(Value::Letter(value_data.iter().collect()));
+            return Ok(Value::Letter(letter));
         }
 
         return Err(Error::ShouldValueHere {
-            column: position.begin,
+            position: *position,
         });
     }
 
@@ -329,10 +489,104 @@ impl<'a> Parser<'a> {
 
     // 当前 token 的位置信息。
     fn current_position(&self) -> Result<&Position> {
-        if let Some(position) = self.positions.get(self.pos) {
+        self.at_position(self.pos)
+    }
+
+    // 指定位置的 token 的位置信息。
+    fn at_position(&self, pos: usize) -> Result<&Position> {
+        if let Some(position) = self.positions.get(pos) {
             Ok(position)
         } else {
-            Err(Error::MissingPosition { index: self.pos })
+            Err(Error::MissingPosition { index: pos })
+        }
+    }
+}
+
+// 解析运算符部分的名称，忽略修饰符。
+fn operator_of(operator_str: &str) -> Option<Operator> {
+    let name = operator_str.split('.').next().unwrap_or_default();
+
+    Operator::from_str(name).ok()
+}
+
+// 为编译条件时产生的错误附加位置信息。
+fn locate(error: Error, span: Position) -> Error {
+    Error::Located {
+        span,
+        source: Box::new(error),
+    }
+}
+
+// 将数字转换为整数或小数，包含 `.` 的视为小数。
+// 参数 `position` 是数字在输入中的位置，用于错误定位。
+fn parse_number(chars: &[char], position: Position) -> Result<Value> {
+    let string_value = chars.iter().collect::<String>();
+
+    if chars.contains(&'.') {
+        string_value
+            .parse::<f64>()
+            .map(Value::Decimal)
+            .map_err(|_| Error::DecimalParseFailed { position })
+    } else {
+        i64::from_str_radix(&string_value, 10)
+            .map(Value::Integer)
+            .map_err(|_| Error::IntegerParseFailed { position })
+    }
+}
+
+// 处理字符串中的转义序列。
+//
+// 支持 `\"`、`\\`、`\n`、`\t` 和 `\u{...}`。其它的反斜杠保持原样，以便在正则表达式中直接使用 `\d` 这类写法。
+// 参数 `position` 是字符串在输入中的位置，用于错误定位。字符串不会跨行。
+fn unescape(chars: &[char], position: Position) -> Result<String> {
+    let mut letter = String::with_capacity(chars.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] != '\\' {
+            letter.push(chars[i]);
+            i += 1;
+            continue;
         }
+
+        match chars.get(i + 1) {
+            Some('"') => letter.push('"'),
+            Some('\\') => letter.push('\\'),
+            Some('n') => letter.push('\n'),
+            Some('t') => letter.push('\t'),
+            Some('u') => {
+                let invalid_escape = || Error::InvalidEscape {
+                    position: position.offset(i),
+                };
+                if chars.get(i + 2) != Some(&'{') {
+                    return Err(invalid_escape());
+                }
+
+                let len = chars[i + 3..]
+                    .iter()
+                    .position(|c| *c == '}')
+                    .ok_or_else(invalid_escape)?;
+                let hex = chars[i + 3..i + 3 + len].iter().collect::<String>();
+                let c = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .filter(|_| (1..=6).contains(&len))
+                    .and_then(std::char::from_u32)
+                    .ok_or_else(invalid_escape)?;
+
+                letter.push(c);
+                // 跳过 `u{...}`，反斜杠在后面统一跳过。
+                i += len + 2;
+            }
+            // 未知的转义序列保持原样。
+            _ => {
+                letter.push('\\');
+                i += 1;
+                continue;
+            }
+        }
+
+        i += 2;
     }
+
+    Ok(letter)
 }
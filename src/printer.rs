@@ -0,0 +1,218 @@
+//! 规则的格式化输出。
+//!
+//! 匹配器、表达式、条件和值都实现了 `Display`，输出单行的规范规则文本。输出的规则可以重新编译为等价的匹配器：
+//! ```
+//! use matchingram::compile_rule;
+//!
+//! let rule = r#"(message.from.is_bot and not message.text any {"a" "b"}) or (message.text.len gt 5)"#;
+//! let matcher = compile_rule(rule)?;
+//!
+//! assert_eq!(matcher.to_string(), rule);
+//! assert_eq!(compile_rule(matcher.to_string())?.to_string(), rule);
+//! # Ok::<(), matchingram::Error>(())
+//! ```
+//! 需要多行输出时，请使用 [`Printer`](struct.Printer.html)。
+
+use std::fmt;
+
+use super::matches::{Cont, Expr, Matcher, Value};
+
+/// 规则格式化器。
+///
+/// 输出多行的规则文本。顶层的每一个条件组独占一行，单行宽度超出限制的组会展开，组内的每一个条件独占一行：
+/// ```text
+/// (message.text.len gt 120 and message.from.is_bot) or
+/// (
+///   not message.from.id eq 10086 and
+///   message.text any {"移动" "联通"}
+/// )
+/// ```
+#[derive(Debug, Clone)]
+pub struct Printer {
+    indent: usize,
+    max_width: usize,
+}
+
+impl Default for Printer {
+    fn default() -> Self {
+        Printer {
+            indent: 2,
+            max_width: 80,
+        }
+    }
+}
+
+impl Printer {
+    /// 创建默认的格式化器（缩进 2 个空格，单行最大宽度 80 个字符）。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置缩进的空格数量。
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+
+        self
+    }
+
+    /// 设置单行的最大宽度（字符数量）。
+    pub fn max_width(mut self, max_width: usize) -> Self {
+        self.max_width = max_width;
+
+        self
+    }
+
+    /// 格式化匹配器。
+    pub fn print(&self, matcher: &Matcher) -> String {
+        self.print_expr(&matcher.expr)
+    }
+
+    /// 格式化表达式。
+    pub fn print_expr(&self, expr: &Expr) -> String {
+        match expr {
+            Expr::Or(exprs) => exprs
+                .iter()
+                .map(|expr| self.group(expr, 0))
+                .collect::<Vec<_>>()
+                .join(" or\n"),
+            expr => self.group(expr, 0),
+        }
+    }
+
+    // 输出括号包裹的表达式。`level` 是左括号所在行的缩进层级。
+    fn group(&self, expr: &Expr, level: usize) -> String {
+        let compact = group(expr);
+        if level * self.indent + compact.chars().count() <= self.max_width {
+            return compact;
+        }
+
+        let (exprs, separator) = match expr {
+            Expr::And(exprs) => (exprs.iter().collect(), " and\n"),
+            Expr::Or(exprs) => (exprs.iter().collect(), " or\n"),
+            expr => (vec![expr], ""),
+        };
+        let inner_indent = " ".repeat((level + 1) * self.indent);
+        let lines = exprs
+            .into_iter()
+            .map(|expr| {
+                let line = match expr {
+                    Expr::And(_) | Expr::Or(_) => self.group(expr, level + 1),
+                    Expr::Not(expr) => format!("not {}", self.group(expr, level + 1)),
+                    Expr::Cont(cont) => cont.to_string(),
+                };
+
+                format!("{}{}", inner_indent, line)
+            })
+            .collect::<Vec<_>>()
+            .join(separator);
+
+        format!("(\n{}\n{})", lines, " ".repeat(level * self.indent))
+    }
+}
+
+// 输出括号包裹的表达式。
+fn group(expr: &Expr) -> String {
+    format!("({})", inner(expr))
+}
+
+// 输出表达式，不包括外层的括号。
+fn inner(expr: &Expr) -> String {
+    match expr {
+        Expr::Cont(cont) => cont.to_string(),
+        Expr::Not(expr) => format!("not {}", group(expr)),
+        Expr::And(exprs) => exprs.iter().map(child).collect::<Vec<_>>().join(" and "),
+        Expr::Or(exprs) => exprs.iter().map(child).collect::<Vec<_>>().join(" or "),
+    }
+}
+
+// 输出子表达式。为了保留表达式的结构，复合的子表达式总是使用括号包裹。
+fn child(expr: &Expr) -> String {
+    match expr {
+        Expr::And(_) | Expr::Or(_) => group(expr),
+        expr => inner(expr),
+    }
+}
+
+// 转义字符串中的特殊字符。
+fn escape(letter: &str) -> String {
+    let mut escaped = String::with_capacity(letter.len());
+
+    for c in letter.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+impl fmt::Display for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Expr::Or(exprs) => {
+                let groups = exprs.iter().map(group).collect::<Vec<_>>();
+
+                write!(f, "{}", groups.join(" or "))
+            }
+            expr => write!(f, "{}", group(expr)),
+        }
+    }
+}
+
+impl fmt::Display for Cont {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_negative {
+            write!(f, "not ")?;
+        }
+        write!(f, "{}", self.scope.field_path(self.field))?;
+
+        if let Some(operator) = &self.operator {
+            write!(f, " {}", operator.to_string())?;
+            for modifier in &self.modifiers {
+                write!(f, ".{}", modifier)?;
+            }
+        }
+
+        if let Some(name) = self.list_name() {
+            write!(f, " ${}", name)?;
+        } else if let Some(value) = &self.value {
+            let is_list = matches!(self.operator, Some(o) if o.takes_list());
+
+            if is_list || value.len() != 1 {
+                let value = value.iter().map(Value::to_string).collect::<Vec<_>>();
+
+                write!(f, " {{{}}}", value.join(" "))?;
+            } else {
+                write!(f, " {}", value[0])?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Letter(v) => write!(f, "\"{}\"", escape(v)),
+            Value::Integer(v) => write!(f, "{}", v),
+            // 小数总是保留小数点，避免被解析为整数。
+            Value::Decimal(v) if v.fract() == 0.0 => write!(f, "{:.1}", v),
+            Value::Decimal(v) => write!(f, "{}", v),
+            Value::Boolean(v) => write!(f, "{}", v),
+            Value::Range(range) => write!(f, "{}..{}", range.start, range.end),
+            Value::List { name } => write!(f, "${}", name),
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! 多规则集合。
+
+use super::matches::{Context, ListProvider, Matcher};
+use super::models::Message;
+use super::result::Result;
+
+/// 规则集合。
+///
+/// 规则集合包含多个具有 ID 的匹配器，可以对一条消息一次性完成所有规则的匹配。
+/// 同一次匹配中的所有规则共享匹配上下文，从消息中计算得到的数据（例如规范化的文本、全名、实体的内容和解析出的链接）只计算一次。
+///
+/// 规则按优先级从高到低的顺序匹配，优先级相同的规则按添加的顺序匹配。
+///
+/// # 例子
+/// ```
+/// use matchingram::RuleSet;
+/// use matchingram::models::Message;
+///
+/// let mut rule_set = RuleSet::new();
+/// rule_set.add("ad", r#"(message.text all {"承接" "广告"})"#)?;
+/// rule_set.add("gambling", r#"(message.text any {"菠菜" "博彩"})"#)?;
+/// rule_set.add_with_priority("command", 10, r#"(message.is_command)"#)?;
+///
+/// let message = Message {
+///     text: Some(format!("承接博彩广告")),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(rule_set.matches(&message)?, vec![&"ad", &"gambling"]);
+/// assert_eq!(rule_set.first_match(&message)?, Some(&"ad"));
+/// # Ok::<(), matchingram::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct RuleSet<K> {
+    // 按优先级从高到低排列的规则。
+    rules: Vec<Rule<K>>,
+}
+
+#[derive(Debug)]
+struct Rule<K> {
+    id: K,
+    priority: i32,
+    matcher: Matcher,
+}
+
+impl<K> Default for RuleSet<K> {
+    fn default() -> Self {
+        RuleSet { rules: vec![] }
+    }
+}
+
+impl<K> RuleSet<K> {
+    /// 创建空的规则集合。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 编译规则并以默认优先级（`0`）添加到集合中。
+    pub fn add<S: Into<String>>(&mut self, id: K, rule: S) -> Result<()> {
+        self.add_with_priority(id, 0, rule)
+    }
+
+    /// 编译规则并以指定的优先级添加到集合中。
+    pub fn add_with_priority<S: Into<String>>(
+        &mut self,
+        id: K,
+        priority: i32,
+        rule: S,
+    ) -> Result<()> {
+        let matcher = Matcher::from_rule(rule)?;
+        self.add_matcher(id, priority, matcher);
+
+        Ok(())
+    }
+
+    /// 添加已编译的匹配器。
+    pub fn add_matcher(&mut self, id: K, priority: i32, matcher: Matcher) {
+        // 插入到所有优先级不低于它的规则之后。
+        let index = self
+            .rules
+            .iter()
+            .position(|rule| rule.priority < priority)
+            .unwrap_or(self.rules.len());
+
+        self.rules.insert(
+            index,
+            Rule {
+                id,
+                priority,
+                matcher,
+            },
+        );
+    }
+
+    /// 规则的数量。
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// 是否为空集合。
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// 匹配消息，返回全部匹配成功的规则 ID。
+    pub fn matches(&self, message: &Message) -> Result<Vec<&K>> {
+        self.matches_context(&Context::new(message))
+    }
+
+    /// 匹配消息，返回全部匹配成功的规则 ID。规则中引用的命名列表从 `lists` 中获取。
+    pub fn matches_with_lists(
+        &self,
+        message: &Message,
+        lists: &dyn ListProvider,
+    ) -> Result<Vec<&K>> {
+        self.matches_context(&Context::with_lists(message, lists))
+    }
+
+    /// 匹配消息，返回第一个（即优先级最高的）匹配成功的规则 ID。剩余的规则不再匹配。
+    pub fn first_match(&self, message: &Message) -> Result<Option<&K>> {
+        self.first_match_context(&Context::new(message))
+    }
+
+    /// 匹配消息，返回第一个匹配成功的规则 ID。规则中引用的命名列表从 `lists` 中获取。
+    pub fn first_match_with_lists(
+        &self,
+        message: &Message,
+        lists: &dyn ListProvider,
+    ) -> Result<Option<&K>> {
+        self.first_match_context(&Context::with_lists(message, lists))
+    }
+
+    fn matches_context(&self, ctx: &Context) -> Result<Vec<&K>> {
+        let mut ids = vec![];
+
+        for rule in &self.rules {
+            if rule.matcher.expr.match_context(ctx)? {
+                ids.push(&rule.id);
+            }
+        }
+
+        Ok(ids)
+    }
+
+    fn first_match_context(&self, ctx: &Context) -> Result<Option<&K>> {
+        for rule in &self.rules {
+            if rule.matcher.expr.match_context(ctx)? {
+                return Ok(Some(&rule.id));
+            }
+        }
+
+        Ok(None)
+    }
+}
@@ -0,0 +1,163 @@
+//! 匹配器的序列化支持（需要启用 `serialize` 特性）。
+//!
+//! 匹配器被序列化为带有版本号的条件表达式树，反序列化时无需再经历词法和语法分析。以 JSON 为例：
+//! ```json
+//! {
+//!   "version": 1,
+//!   "expr": {
+//!     "or": [
+//!       { "and": [{ "cont": { "field": "message.text", "operator": "any", "modifiers": ["i"], "value": ["bot"] } }] },
+//!       { "cont": { "negative": true, "field": "message.from.is_bot" } }
+//!     ]
+//!   }
+//! }
+//! ```
+//!
+//! 条件中的正则表达式和关键字自动机不会被序列化，它们会在反序列化时重新编译。
+
+use serde::de::{self, Deserializer};
+use serde::ser::Serializer;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use super::error::Error;
+use super::matches::{Cont, Expr, Field, Matcher, Scope, Values};
+use super::operator::{Modifier, Operator};
+
+/// 当前的序列化格式版本。
+pub const VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct MatcherRef<'a> {
+    version: u32,
+    expr: &'a Expr,
+}
+
+#[derive(Deserialize)]
+struct MatcherData {
+    version: u32,
+    expr: Expr,
+}
+
+#[derive(Serialize)]
+struct ContRef<'a> {
+    #[serde(skip_serializing_if = "is_false")]
+    negative: bool,
+    field: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    operator: &'a Option<Operator>,
+    #[serde(skip_serializing_if = "<[Modifier]>::is_empty")]
+    modifiers: &'a [Modifier],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: &'a Option<Values>,
+}
+
+#[derive(Deserialize)]
+struct ContData {
+    #[serde(default)]
+    negative: bool,
+    field: String,
+    #[serde(default)]
+    operator: Option<Operator>,
+    #[serde(default)]
+    modifiers: Vec<Modifier>,
+    #[serde(default)]
+    value: Option<Values>,
+}
+
+fn is_false(b: &bool) -> bool {
+    !b
+}
+
+impl Serialize for Matcher {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        MatcherRef {
+            version: VERSION,
+            expr: &self.expr,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Matcher {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = MatcherData::deserialize(deserializer)?;
+        if data.version != VERSION {
+            return Err(de::Error::custom(format!(
+                "unsupported matcher version `{}`, expected `{}`",
+                data.version, VERSION
+            )));
+        }
+
+        Ok(Matcher::from_expr(data.expr))
+    }
+}
+
+impl Serialize for Cont {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ContRef {
+            negative: self.is_negative,
+            field: self.scope.field_path(self.field),
+            operator: &self.operator,
+            modifiers: &self.modifiers,
+            value: &self.value,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Cont {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ContData {
+            negative,
+            field,
+            operator,
+            modifiers,
+            value,
+        } = ContData::deserialize(deserializer)?;
+
+        // 字段可能属于被回复或被置顶的消息。
+        let (scope, field) = Scope::parse_field(&field)
+            .map_err(|_| de::Error::custom(format!("unknown `{}` field", field)))?;
+        // 通过构建函数重新检查条件，并完成运算符需要的预处理。
+        let cont = match (operator, value) {
+            (None, _) => Cont::single_field(negative, scope.field_path(field)),
+            (Some(operator), Some(value)) => Cont::build(negative, field, operator, value)
+                .and_then(|cont| {
+                    let cont = cont.with_scope(scope);
+                    if modifiers.is_empty() {
+                        Ok(cont)
+                    } else {
+                        cont.with_modifiers(modifiers)
+                    }
+                }),
+            (Some(_), None) => Err(Error::FieldRequireValue { field }),
+        };
+
+        cont.map_err(de::Error::custom)
+    }
+}
+
+// 字段、运算符和修饰符都序列化为它们在规则中的字符串形式。
+macro_rules! impl_serde_by_str {
+    ($type:ty, $name:expr) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(&self.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+
+                <$type>::from_str(&s)
+                    .map_err(|_| de::Error::custom(format!("unknown `{}` {}", s, $name)))
+            }
+        }
+    };
+}
+
+impl_serde_by_str!(Field, "field");
+impl_serde_by_str!(Operator, "operator");
+impl_serde_by_str!(Modifier, "modifier");
@@ -0,0 +1,54 @@
+//! 基于编辑距离的拼写建议。
+
+/// 从候选项中找出与输入最接近的一项。
+///
+/// 字段大多共享 `message.` 之类的前缀，因此允许的编辑距离由去除公共前缀后剩余部分的长度决定（三分之一，向上取整）。
+/// 距离相同时选择字典序较小的一项，保证结果稳定。
+pub(crate) fn did_you_mean<I, S>(input: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    candidates
+        .into_iter()
+        .filter_map(|c| {
+            let c = c.as_ref();
+            let common = input
+                .chars()
+                .zip(c.chars())
+                .take_while(|(a, b)| a == b)
+                .count();
+            let rest = input.chars().count() - common;
+            let distance = levenshtein(input, c);
+
+            if distance <= rest.div_ceil(3).max(1) {
+                Some((distance, c.to_owned()))
+            } else {
+                None
+            }
+        })
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
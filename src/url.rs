@@ -0,0 +1,119 @@
+//! 消息中的链接的识别和解析。
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    // 文本中的链接：可选的协议、至少包含一个点的主机名、可选的端口和路径。
+    static ref URL_RE: Regex = Regex::new(
+        r"(?i)(?:[a-z][a-z0-9+.-]*://)?(?:[\p{L}\p{N}](?:[\p{L}\p{N}-]*[\p{L}\p{N}])?\.)+\p{L}{2,}(?::\d{1,5})?(?:[/?#][a-z0-9\-._~:/?#\[\]@!$&'()*+,;=%]*)?"
+    )
+    .unwrap();
+}
+
+// 没有协议的网址只有以这些常见的顶级域名结尾时才被识别，以免将 `setup.exe`、`readme.md` 这样的文件名当作链接。
+// 与常见的文件扩展名相同的顶级域名（例如 `md`、`zip`、`sh`、`py`）不在其中。
+const KNOWN_TLDS: &[&str] = &[
+    "app", "au", "biz", "br", "ca", "cn", "co", "com", "de", "dev", "edu", "eu", "fr", "fun",
+    "gov", "hk", "icu", "id", "in", "info", "io", "ir", "it", "jp", "kr", "kz", "link", "live",
+    "ly", "me", "my", "net", "online", "org", "ph", "pro", "ru", "sg", "shop", "site", "store",
+    "th", "top", "tv", "tw", "ua", "uk", "us", "vip", "vn", "xyz",
+];
+
+// 通常作为二级域名出现在国家和地区顶级域名之下的标签，例如 `co.uk` 和 `com.cn`。
+const SECOND_LEVEL_LABELS: &[&str] = &[
+    "ac", "co", "com", "edu", "go", "gov", "ne", "net", "or", "org",
+];
+
+/// 解析后的链接。
+#[derive(Debug, PartialEq)]
+pub(crate) struct Url {
+    /// 小写的主机名，不包括端口。
+    pub host: String,
+    /// 路径，不包括查询参数和片段。没有路径时为 `/`。
+    pub path: String,
+}
+
+impl Url {
+    /// 解析链接，缺少协议的链接同样可以解析。
+    pub(crate) fn parse(url: &str) -> Option<Self> {
+        let rest = match url.find("://") {
+            Some(i) if is_scheme(&url[..i]) => &url[i + 3..],
+            _ => url,
+        };
+
+        let authority_end = rest.find(['/', '?', '#'].as_ref()).unwrap_or(rest.len());
+        let (authority, rest) = rest.split_at(authority_end);
+        // 去掉用户信息和端口。
+        let host = authority.rsplit('@').next().unwrap_or_default();
+        let host = match host.rfind(':') {
+            Some(i) if host[i + 1..].chars().all(|c| c.is_ascii_digit()) => &host[..i],
+            _ => host,
+        };
+        let host = host.trim_end_matches('.').to_lowercase();
+        if host.is_empty() {
+            return None;
+        }
+
+        let path_end = rest.find(['?', '#'].as_ref()).unwrap_or(rest.len());
+        let path = match &rest[..path_end] {
+            "" => "/".to_owned(),
+            path => path.to_owned(),
+        };
+
+        Some(Url { host, path })
+    }
+
+    /// 可注册的域名，例如 `www.example.co.uk` 的 `example.co.uk`。
+    ///
+    /// 这是不依赖公共后缀列表的近似实现：通常取主机名的最后两级，二级域名是常见的 `co`、`com` 等时取最后三级。
+    pub(crate) fn domain(&self) -> &str {
+        let labels = self.host.split('.').collect::<Vec<_>>();
+        let n = labels.len();
+        let is_ip = labels.iter().all(|l| l.chars().all(|c| c.is_ascii_digit()));
+        if n <= 2 || is_ip {
+            return &self.host;
+        }
+
+        let keep = if labels[n - 1].len() == 2 && SECOND_LEVEL_LABELS.contains(&labels[n - 2]) {
+            3
+        } else {
+            2
+        };
+        let skipped = labels[..n - keep]
+            .iter()
+            .map(|l| l.len() + 1)
+            .sum::<usize>();
+
+        &self.host[skipped.min(self.host.len())..]
+    }
+}
+
+/// 查找文本中的全部链接，链接末尾的标点符号会被去掉。
+///
+/// 没有协议的网址需要以常见的顶级域名结尾，例如 `t.me/abc` 会被识别，`setup.exe` 则不会。
+pub(crate) fn find_urls(text: &str) -> impl Iterator<Item = &str> {
+    URL_RE
+        .find_iter(text)
+        .map(|m| {
+            m.as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?', '\'', '"', ')', ']'].as_ref())
+        })
+        .filter(|url| url.contains("://") || has_known_tld(url))
+}
+
+// 网址的主机名是否以常见的顶级域名结尾。
+fn has_known_tld(url: &str) -> bool {
+    Url::parse(url).is_some_and(|url| {
+        let tld = url.host.rsplit('.').next().unwrap_or_default();
+
+        KNOWN_TLDS.contains(&tld)
+    })
+}
+
+fn is_scheme(s: &str) -> bool {
+    let mut chars = s.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '.' | '-'))
+}
@@ -0,0 +1,76 @@
+use matchingram::{Diagnostic, Matcher};
+
+#[test]
+fn test_diagnostic() {
+    let rule = "(\n  message.text eqq \"hello\"\n)";
+    let err = Matcher::from_rule(rule).unwrap_err();
+    let diagnostic = Diagnostic::new(rule, &err).unwrap();
+
+    assert_eq!(2, diagnostic.line);
+    assert_eq!(16, diagnostic.column);
+    assert_eq!(3, diagnostic.len);
+    assert_eq!(
+        r#"error: unknown operator `eqq`
+ --> 2:16
+  |
+2 |   message.text eqq "hello"
+  |                ^^^
+  = hint: did you mean `eq`?"#,
+        diagnostic.to_string()
+    );
+
+    // 宽字符按显示宽度对齐。
+    let rule = r#"(message.text eq "一二\u{zz}")"#;
+    let err = Matcher::from_rule(rule).unwrap_err();
+    let diagnostic = Diagnostic::new(rule, &err).unwrap();
+
+    assert_eq!(
+        r#"error: invalid escape sequence
+ --> 1:21
+  |
+1 | (message.text eq "一二\u{zz}")
+  |                       ^
+  = hint: supported escapes are `\"`, `\\`, `\n`, `\t` and `\u{...}`"#,
+        diagnostic.to_string()
+    );
+
+    // 制表符被保留，使标记与源码对齐。
+    let rule = "(message.text eq \"a\"\n  and message.text eq \"\t\" and message.text eqq \"b\")";
+    let err = Matcher::from_rule(rule).unwrap_err();
+    let diagnostic = Diagnostic::new(rule, &err).unwrap();
+
+    assert_eq!(2, diagnostic.line);
+    assert_eq!(
+        Some(format!("  | {}\t{}^^^", " ".repeat(23), " ".repeat(19)).as_str()),
+        diagnostic.to_string().lines().nth(4)
+    );
+
+    let rule = "(message.text eq \"a\"\n  and message.text eq \"b\"";
+    let err = Matcher::from_rule(rule).unwrap_err();
+    let diagnostic = Diagnostic::new(rule, &err).unwrap();
+
+    assert_eq!(
+        "error: expected `)`\n --> 2:26\n  |\n2 |   and message.text eq \"b\"\n  |                          ^\n  = hint: every group must be closed with `)`",
+        diagnostic.to_string()
+    );
+
+    // 缺失值的提示取决于运算符接受单个值还是列表。
+    let hint = |rule: &str| {
+        let err = Matcher::from_rule(rule).unwrap_err();
+
+        Diagnostic::new(rule, &err).unwrap().hint.unwrap()
+    };
+
+    assert_eq!(
+        "`eq` takes a single value like `\"a\"` or `1`",
+        hint("(message.text eq )")
+    );
+    assert_eq!(
+        "`any` takes a value or a list like `{\"a\" \"b\"}`",
+        hint("(message.text any.i)")
+    );
+    assert_eq!(
+        "conditions must be joined with `and` or `or`",
+        hint(r#"(message.text eq "a") (message.text eq "b")"#)
+    );
+}
@@ -0,0 +1,134 @@
+use matchingram::explain::Trace;
+use matchingram::matches::{Field, Value};
+use matchingram::models::Message;
+use matchingram::{CompiledList, Error, Matcher};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[test]
+fn test_explain() {
+    let json_data = r#"
+        {
+            "text": "我是移动客服，请加群",
+            "from": {
+                "id": 10000,
+                "first_name": "客服",
+                "is_bot": false
+            }
+        }
+    "#;
+    let message = serde_json::from_str::<Message>(json_data).unwrap();
+
+    let rule = r#"
+        (message.from.is_bot) or
+        (not message.from.id eq 10086 and message.text any.i {"移动" "联通" "请加群"}) or
+        (message.text.len gt 0)
+    "#;
+    let matcher = Matcher::from_rule(rule).unwrap();
+    let explanation = matcher.explain(&message).unwrap();
+
+    assert!(explanation.is_match);
+    assert_eq!(explanation.matched_group, Some(1));
+    // 匹配成功后剩余的组不再求值。
+    assert_eq!(explanation.groups.len(), 2);
+    assert!(!explanation.groups[0].is_match());
+
+    if let Trace::And(traces) = &explanation.groups[1] {
+        assert_eq!(traces.len(), 2);
+        if let Trace::Cont(trace) = &traces[1] {
+            assert_eq!(trace.field, Field::MessageText);
+            assert_eq!(
+                trace.field_value,
+                Some(Value::Letter("我是移动客服，请加群".to_owned()))
+            );
+            assert_eq!(
+                trace.hits,
+                vec![
+                    Value::Letter("移动".to_owned()),
+                    Value::Letter("请加群".to_owned())
+                ]
+            );
+            assert!(trace.is_match);
+        } else {
+            panic!("the trace should be a condition");
+        }
+    } else {
+        panic!("the trace should be an and-expression");
+    }
+
+    let rule = r#"(message.from.last_name eq "Bot" or message.location.latitude gt 0)"#;
+    let matcher = Matcher::from_rule(rule).unwrap();
+    let explanation = matcher.explain(&message).unwrap();
+
+    assert!(!explanation.is_match);
+    assert_eq!(explanation.matched_group, None);
+    // 顶层的 `or` 表达式的每一个子表达式都是一个条件组。
+    assert_eq!(explanation.groups.len(), 2);
+    for trace in &explanation.groups {
+        if let Trace::Cont(trace) = trace {
+            assert_eq!(trace.field_value, None);
+        } else {
+            panic!("the trace should be a condition");
+        }
+    }
+}
+
+#[test]
+fn test_explain_with_lists() {
+    let message = Message {
+        text: Some("出售 TG 账号".to_owned()),
+        ..Default::default()
+    };
+
+    let mut lists: HashMap<String, Arc<CompiledList>> = HashMap::new();
+    lists.insert(
+        "spam_words".to_owned(),
+        Arc::new(vec![Value::from_str("出售"), Value::from_str("代刷")].into()),
+    );
+
+    let matcher = Matcher::from_rule(r#"(message.text any $spam_words)"#).unwrap();
+    assert!(matches!(
+        matcher.explain(&message),
+        Err(Error::UnknownList { .. })
+    ));
+
+    let explanation = matcher.explain_with_lists(&message, &lists).unwrap();
+    assert!(explanation.is_match);
+    assert_eq!(explanation.matched_group, Some(0));
+    if let Trace::Cont(trace) = &explanation.groups[0] {
+        assert_eq!(trace.hits, vec![Value::from_str("出售")]);
+        assert!(trace.is_match);
+    } else {
+        panic!("the trace should be a condition");
+    }
+}
+
+#[test]
+fn test_explain_multiple_field() {
+    let json_data = r#"
+        {
+            "text": "加群 https://t.me/joinchat/abc 或访问 www.example.co.uk/promo",
+            "caption": "点这里"
+        }
+    "#;
+    let message = serde_json::from_str::<Message>(json_data).unwrap();
+
+    let rule = r#"(message.urls.host any {"t.me"}) and (message.entities.mention eq "@spam")"#;
+    let matcher = Matcher::from_rule(rule).unwrap();
+    let explanation = matcher.explain(&message).unwrap();
+
+    if let Trace::And(traces) = &explanation.groups[0] {
+        if let (Trace::Cont(host), Trace::Cont(mention)) = (&traces[0], &traces[1]) {
+            assert_eq!(
+                host.field_value,
+                Some(Value::from_str("t.me www.example.co.uk"))
+            );
+            // 没有提取出内容时不返回值。
+            assert_eq!(mention.field_value, None);
+        } else {
+            panic!("the traces should be conditions");
+        }
+    } else {
+        panic!("the trace should be an and-expression");
+    }
+}
@@ -97,4 +97,161 @@ fn test_lex_number() {
 
     assert!(r.is_err());
     assert_eq!("failed to parse from column 23", r.unwrap_err().to_string());
+
+    // 测试范围解析。
+    let rule = r#"(message.text.len in {1..10 -5..-1.5}) or (message.from.id between 1..2)"#;
+    let input = rule.chars().collect::<Vec<_>>();
+    let mut lexer = Lexer::new(&input);
+
+    lexer.tokenize().unwrap();
+
+    let data = lexer.token_data_owner().unwrap();
+    assert_eq!(Some(&(Range, "1..10".to_owned())), data.get(4));
+    assert_eq!(Some(&(Range, "-5..-1.5".to_owned())), data.get(5));
+    assert_eq!(Some(&(Range, "1..2".to_owned())), data.get(12));
+
+    let rule = r#"(message.text.len between 1..)"#;
+    let input = rule.chars().collect::<Vec<_>>();
+    let mut lexer = Lexer::new(&input);
+    let r = lexer.tokenize();
+
+    assert!(r.is_err());
+    assert_eq!("failed to parse from column 27", r.unwrap_err().to_string());
+
+    // 第一行之后的位置同时显示行和列。
+    let rule = "(message.text eq \"a\")\nor (message.longitude gt 1.2.3)";
+    let input = rule.chars().collect::<Vec<_>>();
+    let mut lexer = Lexer::new(&input);
+    let r = lexer.tokenize();
+
+    assert_eq!(
+        "failed to parse from line 2, column 26",
+        r.unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn test_lex_nested() {
+    let rule = r#"(message.from.is_bot and not (message.text eq "a" or message.text.len gt 5))"#;
+    let input = rule.chars().collect::<Vec<_>>();
+
+    let mut lexer = Lexer::new(&input);
+    lexer.tokenize().unwrap();
+
+    let truthy = [
+        (OpenParenthesis, String::from("(")),
+        (Field, String::from("message.from.is_bot")),
+        (And, String::from("and")),
+        (Not, String::from("not")),
+        (OpenParenthesis, String::from("(")),
+        (Field, String::from("message.text")),
+        (Operator, String::from("eq")),
+        (Quote, String::from("\"")),
+        (Letter, String::from("a")),
+        (Quote, String::from("\"")),
+        (Or, String::from("or")),
+        (Field, String::from("message.text.len")),
+        (Operator, String::from("gt")),
+        (Integer, String::from("5")),
+        (CloseParenthesis, String::from(")")),
+        (CloseParenthesis, String::from(")")),
+        (EOF, String::from("")),
+    ];
+
+    assert_eq!(truthy.len(), lexer.output().len());
+    for (i, mapping) in lexer.token_data_owner().unwrap().into_iter().enumerate() {
+        assert_eq!(truthy[i], mapping);
+    }
+}
+
+#[test]
+fn test_lex_escape() {
+    let rule = r#"(message.text eq "say \"hi\"\\" and message.text re "\d+")"#;
+    let input = rule.chars().collect::<Vec<_>>();
+
+    let mut lexer = Lexer::new(&input);
+    lexer.tokenize().unwrap();
+
+    let truthy = [
+        (OpenParenthesis, String::from("(")),
+        (Field, String::from("message.text")),
+        (Operator, String::from("eq")),
+        (Quote, String::from("\"")),
+        (Letter, String::from(r#"say \"hi\"\\"#)),
+        (Quote, String::from("\"")),
+        (And, String::from("and")),
+        (Field, String::from("message.text")),
+        (Operator, String::from("re")),
+        (Quote, String::from("\"")),
+        (Letter, String::from(r#"\d+"#)),
+        (Quote, String::from("\"")),
+        (CloseParenthesis, String::from(")")),
+        (EOF, String::from("")),
+    ];
+
+    assert_eq!(truthy.len(), lexer.output().len());
+    for (i, mapping) in lexer.token_data_owner().unwrap().into_iter().enumerate() {
+        assert_eq!(truthy[i], mapping);
+    }
+}
+
+#[test]
+fn test_lex_comments() {
+    let rule = r##"# 广告规则
+(
+  message.text any {"广告" /* 常见 */ "推广"} // 关键字
+  and not message.from.is_bot # 排除机器人
+) or (message.text eq "#tag // 不是注释")"##;
+    let input = rule.chars().collect::<Vec<_>>();
+
+    let mut lexer = Lexer::new(&input);
+    lexer.tokenize().unwrap();
+
+    let truthy = [
+        (OpenParenthesis, String::from("(")),
+        (Field, String::from("message.text")),
+        (Operator, String::from("any")),
+        (OpenBrace, String::from("{")),
+        (Quote, String::from("\"")),
+        (Letter, String::from("广告")),
+        (Quote, String::from("\"")),
+        (Quote, String::from("\"")),
+        (Letter, String::from("推广")),
+        (Quote, String::from("\"")),
+        (CloseBrace, String::from("}")),
+        (And, String::from("and")),
+        (Not, String::from("not")),
+        (Field, String::from("message.from.is_bot")),
+        (CloseParenthesis, String::from(")")),
+        (Or, String::from("or")),
+        (OpenParenthesis, String::from("(")),
+        (Field, String::from("message.text")),
+        (Operator, String::from("eq")),
+        (Quote, String::from("\"")),
+        (Letter, String::from("#tag // 不是注释")),
+        (Quote, String::from("\"")),
+        (CloseParenthesis, String::from(")")),
+        (EOF, String::from("")),
+    ];
+
+    assert_eq!(truthy.len(), lexer.output().len());
+    for (i, mapping) in lexer.token_data_owner().unwrap().into_iter().enumerate() {
+        assert_eq!(truthy[i], mapping);
+    }
+
+    // 注释不影响位置信息。
+    let rule = "/* 注释 */ (message.txt eq \"a\")";
+    let err = matchingram::Matcher::from_rule(rule).unwrap_err();
+    assert_eq!(Some(10), err.span().map(|span| span.begin));
+
+    let rule = "(message.text eq \"a\") /* 未闭合";
+    let input = rule.chars().collect::<Vec<_>>();
+    let mut lexer = Lexer::new(&input);
+    let r = lexer.tokenize();
+
+    assert!(r.is_err());
+    assert_eq!(
+        "unterminated block comment from column 23",
+        r.unwrap_err().to_string()
+    );
 }
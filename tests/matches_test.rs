@@ -46,3 +46,847 @@ fn test_matcher() {
     let rule = r#"(message.from.first_name hd "Rus")"#;
     assert!(rule_match_json(rule, json_data).unwrap());
 }
+
+#[test]
+fn test_re_operator() {
+    let json_data = r#"
+        {
+            "text": "加V信 123456 了解详情",
+            "from": {
+                "id": 1000012,
+                "first_name": "Rust",
+                "is_bot": false
+            }
+        }
+    "#;
+
+    let rule = r#"(message.text re "加\s*[vV]\s*信\s*\d{6}")"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.text re "^\d+$")"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.text re_any {"^\d+$" "\d{6}"})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.from.full_name re_any {"(?i)bot$" "^\d+$"})"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.caption re ".*")"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    // 不合法的正则表达式在编译时报错。
+    let rule = r#"(message.text re_any {"\d+" "[a-z"})"#;
+    let r = rule_match_json(rule, json_data);
+    assert!(r.is_err());
+    let err = r.unwrap_err();
+    assert!(matches!(
+        err.inner(),
+        matchingram::Error::InvalidRegex { .. }
+    ));
+    assert_eq!(Some(21), err.span().map(|span| span.begin));
+}
+
+#[test]
+fn test_lt_and_td_operator() {
+    let json_data = r#"
+        {
+            "text": "请下载 setup.exe",
+            "caption": "附件说明",
+            "from": {
+                "id": 99,
+                "first_name": "Rust",
+                "is_bot": false
+            },
+            "location": {
+                "longitude": 120.5,
+                "latitude": -0.5
+            }
+        }
+    "#;
+
+    let rule = r#"(message.from.id lt 100)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.from.id lt 99)"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.text.len lt 13)"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.caption.len lt 5)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.location.latitude lt 0)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.text td ".exe")"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.text hd "请下载")"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.caption td "附件")"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.from.first_name td "st")"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.from.last_name td "st")"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+}
+
+#[test]
+fn test_modifiers() {
+    use matchingram::error::Error;
+
+    let json_data = r#"
+        {
+            "text": "Ｂｕｙ ＢＯＴ now",
+            "from": {
+                "id": 1,
+                "first_name": "Spam",
+                "last_name": "Bot",
+                "is_bot": false
+            }
+        }
+    "#;
+
+    let rule = r#"(message.from.full_name any {"bot"})"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.from.full_name any.i {"bot"})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.text any.i {"bot"})"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.text any.nfkc {"BOT"})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.text hd.i.nfkc "buy bot")"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.from.first_name eq.nfkc.i "ＳＰＡＭ" and not message.from.last_name eq.i "bot")"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.from.language_code eq.i "en")"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.text any.x {"bot"})"#;
+    assert!(matches!(
+        rule_match_json(rule, json_data).unwrap_err().inner(),
+        Error::UnknownModifier { .. }
+    ));
+
+    let rule = r#"(message.text re.i "bot")"#;
+    assert!(matches!(
+        rule_match_json(rule, json_data).unwrap_err().inner(),
+        Error::UnsupportedModifier { .. }
+    ));
+
+    let rule = r#"(message.from.id eq.i 1)"#;
+    assert!(matches!(
+        rule_match_json(rule, json_data).unwrap_err().inner(),
+        Error::UnsupportedModifier { .. }
+    ));
+}
+
+#[test]
+fn test_shared_matcher() {
+    use matchingram::models::Message;
+    use matchingram::Matcher;
+    use std::sync::Arc;
+    use std::thread;
+
+    let rule = r#"(message.text any.i {"hello"} and not message.from.is_bot)"#;
+    let matcher = Arc::new(Matcher::from_rule(rule).unwrap());
+
+    let handles = (0..4)
+        .map(|i| {
+            let matcher = Arc::clone(&matcher);
+
+            thread::spawn(move || {
+                let message = Message {
+                    text: Some(format!("HELLO #{}", i)),
+                    reply_to_message: Some(Arc::new(Message::default())),
+                    ..Default::default()
+                };
+
+                matcher.match_message(&message).unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for handle in handles {
+        assert!(handle.join().unwrap());
+    }
+}
+
+#[test]
+fn test_keywords_automaton() {
+    let json_data = r#"
+        {
+            "text": "出售 ＴＧ 账号，价格私聊"
+        }
+    "#;
+
+    // 足够多的关键字会被编译为自动机。
+    let keywords = (0..32)
+        .map(|i| format!(r#""关键字{}""#, i))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let rule = format!(r#"(message.text any {{{} "账号"}})"#, keywords);
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = format!(r#"(message.text any {{{} "tg"}})"#, keywords);
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = format!(r#"(message.text any.i.nfkc {{{} "tg"}})"#, keywords);
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = format!(r#"(message.text all {{{} "账号"}})"#, keywords);
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let duplicates = vec![r#""出售" "账号" "私聊""#; 8].join(" ");
+    let rule = format!(r#"(message.text all {{{}}})"#, duplicates);
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = format!(r#"(message.text all {{{} "价格私聊" "格私"}})"#, duplicates);
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = format!(r#"(not message.text all {{{} "购买"}})"#, duplicates);
+    assert!(rule_match_json(rule, json_data).unwrap());
+}
+
+#[test]
+fn test_suggestions() {
+    use matchingram::Matcher;
+
+    let message = |rule: &str| Matcher::from_rule(rule).unwrap_err().inner().to_string();
+
+    assert_eq!(
+        "unknown field `message.txt`, did you mean `message.text`?",
+        message(r#"(message.txt eq "hello")"#)
+    );
+    assert_eq!(
+        "unknown field `message.from.is_robot`, did you mean `message.from.is_bot`?",
+        message(r#"(message.from.is_robot)"#)
+    );
+    assert_eq!(
+        "unknown field `message.reactions`",
+        message(r#"(message.reactions eq "hello")"#)
+    );
+    assert_eq!(
+        "unknown operator `anyy.i`, did you mean `any.i`?",
+        message(r#"(message.text anyy.i {"hello"})"#)
+    );
+    assert_eq!(
+        "unknown operator `contains`",
+        message(r#"(message.text contains {"hello"})"#)
+    );
+    assert_eq!(
+        "the field `message.from.id` does not support the `any` operator, supported operators are `eq`, `gt`, `lt`, `ge`, `le`, `in`, `between`",
+        message(r#"(message.from.id any {"1"})"#)
+    );
+}
+
+#[test]
+fn test_value_checking() {
+    use matchingram::error::Error;
+    use matchingram::Matcher;
+
+    let check = |rule: &str| {
+        let err = Matcher::from_rule(rule).unwrap_err();
+        let span = err.span().unwrap();
+
+        (err, (span.begin, span.end))
+    };
+
+    let (err, span) = check(r#"(message.from.id eq "abc")"#);
+    assert!(matches!(err.inner(), Error::NotAnInteger { .. }));
+    assert_eq!((20, 25), span);
+
+    let (err, span) = check(r#"(message.text hd {"a" "b"})"#);
+    assert!(matches!(err.inner(), Error::ExpectedSingleValue { .. }));
+    assert_eq!((17, 26), span);
+
+    let (err, _) = check(r#"(message.text hd {"a"})"#);
+    assert!(matches!(err.inner(), Error::ExpectedSingleValue { .. }));
+
+    let (err, _) = check(r#"(message.text any {})"#);
+    assert!(matches!(err.inner(), Error::EmptyList { .. }));
+
+    let (err, span) = check(r#"(message.text in {"a" 1})"#);
+    assert!(matches!(err.inner(), Error::NotAString { .. }));
+    assert_eq!((17, 24), span);
+
+    let (err, _) = check(r#"(message.text.len gt 1.5)"#);
+    assert!(matches!(err.inner(), Error::NotAnInteger { .. }));
+
+    // 小数字段也接受整数，列表运算符也接受单个值。
+    assert!(Matcher::from_rule(r#"(message.location.latitude lt 0)"#).is_ok());
+    assert!(Matcher::from_rule(r#"(message.text any "a")"#).is_ok());
+}
+
+#[test]
+fn test_boolean_values() {
+    use matchingram::error::Error;
+    use matchingram::Matcher;
+
+    let json_data = r#"
+        {
+            "text": "hello",
+            "from": {
+                "id": 1,
+                "first_name": "Bot",
+                "is_bot": true
+            }
+        }
+    "#;
+
+    let rule = r#"(message.from.is_bot eq true)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.from.is_bot eq false)"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(not message.from.is_bot eq false and message.reply_to_message eq false)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.sticker.is_animated eq false and message.sticker eq false)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    // 规范输出中保留布尔值。
+    let matcher = Matcher::from_rule(r#"(message.from.is_bot eq true)"#).unwrap();
+    assert_eq!("(message.from.is_bot eq true)", matcher.to_string());
+
+    let err = Matcher::from_rule(r#"(message.from.is_bot eq "true")"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::NotABoolean { .. }));
+
+    let err = Matcher::from_rule(r#"(message.from.id eq true)"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::NotAnInteger { .. }));
+
+    // 非布尔字段不能单独作为条件。
+    let err = Matcher::from_rule(r#"(message.text)"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::FieldRequireOperator { .. }));
+}
+
+#[test]
+fn test_range_values() {
+    use matchingram::error::Error;
+    use matchingram::Matcher;
+
+    let json_data = r#"
+        {
+            "text": "hello",
+            "from": {
+                "id": 10086,
+                "first_name": "Bot",
+                "is_bot": false
+            },
+            "location": {
+                "longitude": 116.39,
+                "latitude": 39.91
+            }
+        }
+    "#;
+
+    let rule = r#"(message.text.len between 1..5)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.text.len between 6..10)"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.from.id in {1 10000..10100})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.from.id in {1 10086})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.from.id in {1 2..3})"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.location.longitude between 73.5..135 and message.location.latitude between 3.8..53.6)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    // 规范输出中保留范围。
+    let matcher = Matcher::from_rule(r#"(message.location.latitude between 3.8..53)"#).unwrap();
+    assert_eq!(
+        "(message.location.latitude between 3.8..53)",
+        matcher.to_string()
+    );
+
+    let err = Matcher::from_rule(r#"(message.from.id between 10..1)"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::InvalidRange { .. }));
+
+    let err = Matcher::from_rule(r#"(message.from.id between 10)"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::NotARange { .. }));
+
+    let err = Matcher::from_rule(r#"(message.from.id between 1.5..10)"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::NotAnInteger { .. }));
+
+    let err = Matcher::from_rule(r#"(message.from.id eq 1..10)"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::NotAnInteger { .. }));
+}
+
+#[test]
+fn test_integer_set() {
+    let json_data = r#"
+        {
+            "text": "我是移动客服",
+            "from": {
+                "id": 10086,
+                "first_name": "客服",
+                "is_bot": false
+            }
+        }
+    "#;
+
+    let rule = r#"(not message.from.id in {10086 10010} and message.text any {"移动" "联通"})"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    // 足够多的整数会被构建为哈希集合。
+    let ids = (0..32).map(|i| i.to_string()).collect::<Vec<_>>().join(" ");
+
+    let rule = format!(r#"(message.from.id in {{{} 10086}})"#, ids);
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = format!(r#"(message.from.id in {{{}}})"#, ids);
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    // 集合之外的范围仍然有效。
+    let rule = format!(r#"(message.from.id in {{{} 10000..10100}})"#, ids);
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = format!(r#"(message.text.len in {{{}}})"#, ids);
+    assert!(rule_match_json(rule, json_data).unwrap());
+}
+
+#[test]
+fn test_named_lists() {
+    use matchingram::error::Error;
+    use matchingram::matches::Value;
+    use matchingram::models::Message;
+    use matchingram::{CompiledList, Matcher, RuleSet};
+    use std::collections::HashMap;
+    use std::sync::{Arc, RwLock};
+
+    let message: Message = serde_json::from_str(
+        r#"
+        {
+            "text": "出售 TG 账号",
+            "from": {
+                "id": 10086,
+                "first_name": "客服",
+                "is_bot": false
+            }
+        }
+    "#,
+    )
+    .unwrap();
+
+    let mut lists: HashMap<String, Arc<CompiledList>> = HashMap::new();
+    lists.insert(
+        "spam_words".to_owned(),
+        Arc::new(vec![Value::from_str("出售"), Value::from_str("代刷")].into()),
+    );
+    lists.insert(
+        "blocked_ids".to_owned(),
+        Arc::new(vec![Value::Integer(10010)].into()),
+    );
+
+    let matcher = Matcher::from_rule(r#"(message.text any $spam_words)"#).unwrap();
+    assert_eq!("(message.text any $spam_words)", matcher.to_string());
+    assert!(matcher.match_message_with_lists(&message, &lists).unwrap());
+
+    // 更新列表后无需重新编译规则。
+    let rule = r#"(message.from.id in $blocked_ids)"#;
+    let matcher = Matcher::from_rule(rule).unwrap();
+    assert!(!matcher.match_message_with_lists(&message, &lists).unwrap());
+
+    lists.insert(
+        "blocked_ids".to_owned(),
+        Arc::new(vec![Value::Integer(10010), Value::Integer(10086)].into()),
+    );
+    assert!(matcher.match_message_with_lists(&message, &lists).unwrap());
+
+    lists.insert("blocked_ids".to_owned(), Arc::new(vec![].into()));
+    assert!(!matcher.match_message_with_lists(&message, &lists).unwrap());
+
+    // 修饰符同样作用于列表中的值。
+    lists.insert(
+        "brands".to_owned(),
+        Arc::new(vec![Value::from_str("tg")].into()),
+    );
+    let matcher = Matcher::from_rule(r#"(message.text any.i $brands)"#).unwrap();
+    assert!(matcher.match_message_with_lists(&message, &lists).unwrap());
+
+    let mut rule_set = RuleSet::new();
+    rule_set
+        .add("spam", r#"(message.text any $spam_words)"#)
+        .unwrap();
+    rule_set
+        .add("brand", r#"(not message.text any.i $brands)"#)
+        .unwrap();
+    assert_eq!(
+        rule_set.matches_with_lists(&message, &lists).unwrap(),
+        vec![&"spam"]
+    );
+
+    // 列表不存在或类型不符时匹配出错。
+    let matcher = Matcher::from_rule(r#"(message.text any $unknown)"#).unwrap();
+    let err = matcher
+        .match_message_with_lists(&message, &lists)
+        .unwrap_err();
+    assert!(matches!(err, Error::UnknownList { .. }));
+    assert!(matches!(
+        matcher.match_message(&message).unwrap_err(),
+        Error::UnknownList { .. }
+    ));
+
+    let matcher = Matcher::from_rule(r#"(message.from.id in $spam_words)"#).unwrap();
+    let err = matcher
+        .match_message_with_lists(&message, &lists)
+        .unwrap_err();
+    assert!(matches!(err, Error::NotAnInteger { .. }));
+
+    let err = Matcher::from_rule(r#"(message.text eq $spam_words)"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::UnsupportedList { .. }));
+
+    let err = Matcher::from_rule(r#"(message.text any {$spam_words})"#).unwrap_err();
+    assert!(matches!(err, Error::ShouldValueHere { .. }));
+
+    // 较大的列表同样会被编译为关键字自动机和哈希集合，并且可以在匹配的同时被更新。
+    let words = (0..100).map(|i| Value::from_str(&format!("词{}", i)));
+    let ids = (0..100).map(|i| Value::Integer(20000 + i));
+    let mut lists: HashMap<String, Arc<CompiledList>> = HashMap::new();
+    lists.insert(
+        "spam_words".to_owned(),
+        Arc::new(
+            words
+                .chain(vec![Value::from_str("TG")])
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+    );
+    lists.insert(
+        "blocked_ids".to_owned(),
+        Arc::new(ids.collect::<Vec<_>>().into()),
+    );
+    let lists = RwLock::new(lists);
+
+    let matcher = Matcher::from_rule(r#"(message.text any $spam_words)"#).unwrap();
+    assert!(matcher.match_message_with_lists(&message, &lists).unwrap());
+    assert!(matcher.match_message_with_lists(&message, &lists).unwrap());
+
+    let matcher = Matcher::from_rule(r#"(message.from.id in $blocked_ids)"#).unwrap();
+    assert!(!matcher.match_message_with_lists(&message, &lists).unwrap());
+
+    lists.write().unwrap().insert(
+        "blocked_ids".to_owned(),
+        Arc::new(vec![Value::Integer(10086)].into()),
+    );
+    assert!(matcher.match_message_with_lists(&message, &lists).unwrap());
+}
+
+#[test]
+fn test_entity_fields() {
+    use matchingram::matches::Value;
+    use matchingram::Matcher;
+
+    // 😀 占用两个 UTF-16 单元，实体的偏移量以 UTF-16 单元计算。
+    let json_data = r#"
+        {
+            "text": "😀 加入 @spam_bot 领取 https://t.me/joinchat #福利 点这里",
+            "entities": [
+                {"type": "mention", "offset": 6, "length": 9},
+                {"type": "url", "offset": 19, "length": 21},
+                {"type": "hashtag", "offset": 41, "length": 3},
+                {"type": "text_link", "offset": 45, "length": 3, "url": "https://example.com/"}
+            ],
+            "caption_entities": [
+                {"type": "url", "offset": 0, "length": 100}
+            ]
+        }
+    "#;
+
+    let rule = r#"(message.entities.mention eq "@spam_bot")"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.entities.url eq "https://t.me/joinchat")"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r##"(message.entities.hashtag eq "#福利")"##;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.entities.text_link.url any.i {"EXAMPLE.COM"})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(not message.entities.url any {"example.com"})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.entities.count eq 5 and message.entities.count between 1..5)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    // 没有实体的消息不匹配任何实体条件。
+    let rule = r#"(message.entities.url re ".*" or message.entities.count gt 0)"#;
+    assert!(!rule_match_json(rule, r#"{"text": "https://t.me/joinchat"}"#).unwrap());
+
+    // 匹配解释中可以看到命中的关键字。
+    let matcher = Matcher::from_rule(r#"(message.entities.url any {"t.me" "example"})"#).unwrap();
+    let message = serde_json::from_str(json_data).unwrap();
+    let explanation = matcher.explain(&message).unwrap();
+    if let matchingram::explain::Trace::Cont(trace) = &explanation.groups[0] {
+        assert_eq!(trace.hits, vec![Value::from_str("t.me")]);
+    } else {
+        panic!("expected a condition trace");
+    }
+}
+
+#[test]
+fn test_url_fields() {
+    use matchingram::error::Error;
+    use matchingram::matches::Value;
+    use matchingram::models::Message;
+    use matchingram::{CompiledList, Matcher};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    let json_data = r#"
+        {
+            "text": "加群 https://T.me/joinchat/abc?start=1 或访问 www.spam.example.co.uk/promo。",
+            "caption": "点这里",
+            "caption_entities": [
+                {"type": "text_link", "offset": 0, "length": 3, "url": "http://user@cdn.evil.com:8080"}
+            ]
+        }
+    "#;
+
+    let rule = r#"(message.urls.host eq "t.me" and message.urls.path eq "/joinchat/abc")"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.urls.domain eq "example.co.uk" and message.urls.path eq "/promo")"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.urls.host eq "cdn.evil.com" and message.urls.path eq "/")"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    // 通配域名匹配域名本身及其子域名。
+    let rule = r#"(message.urls.host domain_in {"*.EVIL.com"})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.urls.host domain_in {"example.co.uk" "evil.com"})"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.urls.domain domain_in {"*.co.uk"})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(not message.urls.host domain_in {"*.telegram.org" "*.me"})"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    // 普通的文本不会被识别为链接。
+    let rule = r#"(message.urls.host re ".*")"#;
+    assert!(!rule_match_json(rule, r#"{"text": "版本 1.2，价格 3.5 元"}"#).unwrap());
+
+    // 没有协议时，像文件名一样的内容也不会被识别为链接。
+    let text = r#"{"text": "先运行 setup.exe，再看 README.md 和 photos.zip"}"#;
+    assert!(!rule_match_json(rule, text).unwrap());
+
+    let rule = r#"(message.urls.host any {"setup" "readme" "photos"})"#;
+    assert!(!rule_match_json(rule, text).unwrap());
+
+    // 带有协议的链接不受顶级域名的限制。
+    let rule = r#"(message.urls.host eq "files.example.zip")"#;
+    assert!(!rule_match_json(rule, r#"{"text": "files.example.zip/a"}"#).unwrap());
+    assert!(rule_match_json(rule, r#"{"text": "https://files.example.zip/a"}"#).unwrap());
+
+    let rule = r#"(message.urls.host domain_in {"*.l.com"})"#;
+    assert!(!rule_match_json(rule, r#"{"text": "https://evil.com"}"#).unwrap());
+
+    let message: Message = serde_json::from_str(json_data).unwrap();
+    let mut lists: HashMap<String, Arc<CompiledList>> = HashMap::new();
+    lists.insert(
+        "bad_domains".to_owned(),
+        Arc::new(vec![Value::from_str("*.example.co.uk")].into()),
+    );
+    let matcher = Matcher::from_rule(r#"(message.urls.host domain_in $bad_domains)"#).unwrap();
+    assert!(matcher.match_message_with_lists(&message, &lists).unwrap());
+
+    lists.insert(
+        "bad_domains".to_owned(),
+        Arc::new(vec![Value::from_str("a/b")].into()),
+    );
+    let err = matcher
+        .match_message_with_lists(&message, &lists)
+        .unwrap_err();
+    assert!(matches!(err.inner(), Error::InvalidDomain { .. }));
+
+    let err = Matcher::from_rule(r#"(message.urls.host domain_in {"*.*.com"})"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::InvalidDomain { .. }));
+
+    let err = Matcher::from_rule(r#"(message.urls.path domain_in {"t.me"})"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::UnsupportedOperator { .. }));
+}
+
+#[test]
+fn test_quantified_fields() {
+    use matchingram::error::Error;
+    use matchingram::Matcher;
+
+    let json_data = r#"
+        {
+            "new_chat_members": [
+                {"id": 10086, "first_name": "Spam", "last_name": "Bot", "is_bot": true},
+                {"id": 10010, "first_name": "小明", "is_bot": false}
+            ],
+            "photo": [
+                {"width": 90, "height": 60, "file_size": 1024},
+                {"width": 1280, "height": 853}
+            ],
+            "text": "/start@jobs_bot",
+            "entities": [{"type": "bot_command", "offset": 0, "length": 15}],
+            "caption_entities": [{"type": "bold", "offset": 0, "length": 1}]
+        }
+    "#;
+
+    let rule = r#"(message.new_chat_members.any.full_name any.i {"bot"})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+    let rule = r#"(message.new_chat_members.all.full_name any.i {"bot"})"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    let rule =
+        r#"(message.new_chat_members.any.is_bot and not message.new_chat_members.all.is_bot)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+    let rule = r#"(message.new_chat_members.any.is_bot eq false)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule =
+        r#"(message.new_chat_members.count eq 2 and message.new_chat_members.all.id ge 10010)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+    let rule = r#"(message.new_chat_members.any.id in {10000..10009 10086})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    // 缺少文件大小的尺寸不满足条件。
+    let rule = r#"(message.photo.any.width ge 1280 and message.photo.count eq 2)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+    let rule = r#"(message.photo.all.file_size lt 2048)"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+    let rule = r#"(message.photo.any.file_size lt 2048)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.entities.all.type in {"bot_command" "bold"})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+    let rule = r#"(message.entities.all.type eq "bot_command")"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+
+    // 列表为空时 `all` 同样不匹配。
+    let rule = r#"(message.new_chat_members.all.is_bot)"#;
+    assert!(!rule_match_json(rule, r#"{"text": "hello"}"#).unwrap());
+    let rule = r#"(message.new_chat_members.count eq 0 and message.photo.count eq 0)"#;
+    assert!(rule_match_json(rule, r#"{"text": "hello"}"#).unwrap());
+
+    let err = Matcher::from_rule(r#"(message.new_chat_members.any.id)"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::FieldRequireOperator { .. }));
+    let err = Matcher::from_rule(r#"(message.photo.all.width any {"1"})"#).unwrap_err();
+    assert!(matches!(err.inner(), Error::UnsupportedOperator { .. }));
+}
+
+#[test]
+fn test_scoped_fields() {
+    use matchingram::error::Error;
+    use matchingram::Matcher;
+
+    let json_data = r#"
+        {
+            "text": "+1",
+            "from": {"id": 10010, "first_name": "小明", "is_bot": false},
+            "reply_to_message": {
+                "text": "限时福利，加群领取",
+                "from": {"id": 10086, "first_name": "Spam", "is_bot": true},
+                "new_chat_members": [{"id": 10000, "first_name": "Bot", "is_bot": true}]
+            }
+        }
+    "#;
+
+    let rule =
+        r#"(message.reply_to_message.from.is_bot and message.reply_to_message.text any {"福利"})"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+    let rule = r#"(message.reply_to_message.from.id eq 10010)"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+    let rule = r#"(message.reply_to_message.new_chat_members.all.is_bot)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    // 所属的消息不存在时条件不成立。
+    let rule = r#"(message.pinned_message.text eq "+1")"#;
+    assert!(!rule_match_json(rule, json_data).unwrap());
+    let rule = r#"(not message.pinned_message.from.is_bot)"#;
+    assert!(rule_match_json(rule, json_data).unwrap());
+
+    let rule = r#"(message.reply_to_message.text.len gt 5 and not message.reply_to_message.from.is_bot eq false)"#;
+    let matcher = Matcher::from_rule(rule).unwrap();
+    assert_eq!(matcher.to_string(), rule);
+
+    let err = Matcher::from_rule(r#"(message.reply_to_message.txt eq "a")"#).unwrap_err();
+    match err.inner() {
+        Error::UnknownField { field, suggestion } => {
+            assert_eq!(field, "message.reply_to_message.txt");
+            assert_eq!(suggestion.as_deref(), Some("message.reply_to_message.text"));
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn test_forward_and_sender_fields() {
+    let channel_spam = r#"
+        {
+            "sender_chat": {"id": -1001234567890, "type": "channel", "title": "福利频道"},
+            "forward_from_chat": {"id": -1001234567890, "type": "channel", "title": "福利频道"},
+            "forward_date": 1609459200,
+            "text": "限时福利"
+        }
+    "#;
+    let hidden_user = r#"
+        {
+            "forward_sender_name": "Spam Bot",
+            "forward_date": 1609459200,
+            "via_bot": {"id": 10000, "first_name": "GIF", "username": "gif", "is_bot": true},
+            "text": "限时福利"
+        }
+    "#;
+    let forwarded_bot = r#"
+        {
+            "forward_from": {"id": 10086, "first_name": "Spam", "last_name": "Bot", "username": "spam_bot", "is_bot": true},
+            "forward_date": 1609459200
+        }
+    "#;
+
+    let rule =
+        r#"(message.sender_chat.type eq "channel" and message.sender_chat.title any {"福利"})"#;
+    assert!(rule_match_json(rule, channel_spam).unwrap());
+    let rule = r#"(message.sender_chat.id in {-1001234567890})"#;
+    assert!(rule_match_json(rule, channel_spam).unwrap());
+    let rule = r#"(message.sender_chat)"#;
+    assert!(!rule_match_json(rule, hidden_user).unwrap());
+
+    let rule = r#"(message.is_forwarded)"#;
+    assert!(rule_match_json(rule, channel_spam).unwrap());
+    assert!(rule_match_json(rule, hidden_user).unwrap());
+    assert!(rule_match_json(rule, forwarded_bot).unwrap());
+    assert!(!rule_match_json(rule, r#"{"text": "hello"}"#).unwrap());
+
+    let rule =
+        r#"(message.forward_sender_name any.i {"bot"} and message.forward_date ge 1609459200)"#;
+    assert!(rule_match_json(rule, hidden_user).unwrap());
+    let rule = r#"(message.via_bot.username eq "gif" and message.via_bot.id eq 10000)"#;
+    assert!(rule_match_json(rule, hidden_user).unwrap());
+
+    let rule = r#"(message.forward_from.is_bot and message.forward_from.full_name eq "SpamBot")"#;
+    assert!(rule_match_json(rule, forwarded_bot).unwrap());
+    let rule = r#"(message.forward_from.username re "_bot$" and message.forward_from.id eq 10086)"#;
+    assert!(rule_match_json(rule, forwarded_bot).unwrap());
+    let rule = r#"(not message.forward_from.last_name eq "Bot")"#;
+    assert!(rule_match_json(rule, hidden_user).unwrap());
+}
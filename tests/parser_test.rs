@@ -11,7 +11,7 @@ fn test_parser() {
     lexer.tokenize().unwrap();
 
     let parser = Parser::new(&mut lexer).unwrap();
-    let mut matcher = parser.parse().unwrap();
+    let matcher = parser.parse().unwrap();
 
     let text1 = format!("Jay say: Hello!");
     let text2 = format!("小明说：你好！");
@@ -41,7 +41,7 @@ fn test_parse_number() {
     let input = rule.chars().collect::<Vec<_>>();
     let mut lexer = Lexer::new(&input);
     let parser = Parser::new(&mut lexer).unwrap();
-    let mut matcher = parser.parse().unwrap();
+    let matcher = parser.parse().unwrap();
 
     // TODO: 以下的 assertions 应该以测试 Matcher 结构的字段内容为主，而不是测试匹配结果。
 
@@ -60,3 +60,184 @@ fn test_parse_number() {
     assert!(matcher.match_message(&message1).unwrap());
     assert!(matcher.match_message(&message2).unwrap());
 }
+
+#[test]
+fn test_parse_nested() {
+    use matchingram::matches::Expr;
+    use matchingram::models::User;
+
+    let rule = r#"message.from.is_bot or not (message.text any {"a" "b"} and (message.text.len gt 5 or message.text eq "a"))"#;
+    let input = rule.chars().collect::<Vec<_>>();
+    let mut lexer = Lexer::new(&input);
+    let parser = Parser::new(&mut lexer).unwrap();
+    let matcher = parser.parse().unwrap();
+
+    // 优先级：`not` 高于 `and` 高于 `or`。
+    match &matcher.expr {
+        Expr::Or(exprs) => {
+            assert_eq!(2, exprs.len());
+            assert!(matches!(exprs[0], Expr::Cont(_)));
+            match &exprs[1] {
+                Expr::Not(expr) => match expr.as_ref() {
+                    Expr::And(exprs) => {
+                        assert_eq!(2, exprs.len());
+                        assert!(matches!(&exprs[1], Expr::Or(exprs) if exprs.len() == 2));
+                    }
+                    _ => panic!("expected an `and` expression"),
+                },
+                _ => panic!("expected a `not` expression"),
+            }
+        }
+        _ => panic!("expected an `or` expression"),
+    }
+
+    let message1 = Message {
+        text: Some(String::from("a")),
+        ..Default::default()
+    };
+    let message2 = Message {
+        text: Some(String::from("abc")),
+        ..Default::default()
+    };
+    let message3 = Message {
+        text: Some(String::from("a")),
+        from: Some(User {
+            id: 1,
+            is_bot: true,
+            first_name: String::from("Bot"),
+            last_name: None,
+            username: None,
+            language_code: None,
+        }),
+        ..Default::default()
+    };
+
+    assert!(!matcher.match_message(&message1).unwrap());
+    assert!(matcher.match_message(&message2).unwrap());
+    assert!(matcher.match_message(&message3).unwrap());
+
+    let rule = r#"(message.text eq "a" and (message.text.len gt 5)"#;
+    let input = rule.chars().collect::<Vec<_>>();
+    let mut lexer = Lexer::new(&input);
+    let parser = Parser::new(&mut lexer).unwrap();
+    let r = parser.parse();
+
+    assert!(r.is_err());
+    assert_eq!(
+        "it should be `)` (--> column 49)",
+        r.unwrap_err().to_string()
+    );
+}
+
+#[test]
+fn test_parse_escape() {
+    use matchingram::error::Error;
+    use matchingram::matches::{Expr, Value};
+
+    let parse = |rule: &str| {
+        let input = rule.chars().collect::<Vec<_>>();
+        let mut lexer = Lexer::new(&input);
+        let parser = Parser::new(&mut lexer)?;
+
+        parser.parse()
+    };
+
+    let matcher =
+        parse(r#"(message.text in {"say \"hi\"" "a\\b" "\n\t" "\u{1F600}\u{4e2d}" "\d"})"#)
+            .unwrap();
+    if let Expr::Cont(cont) = &matcher.expr {
+        assert_eq!(
+            cont.value,
+            Some(vec![
+                Value::from_str("say \"hi\""),
+                Value::from_str("a\\b"),
+                Value::from_str("\n\t"),
+                Value::from_str("😀中"),
+                // 未知的转义序列保持原样。
+                Value::from_str("\\d"),
+            ])
+        );
+    } else {
+        panic!("the expression should be a condition");
+    }
+
+    let message = Message {
+        text: Some(format!("he said \"hi\"\n")),
+        ..Default::default()
+    };
+    assert!(parse(r#"(message.text td "\"hi\"\n")"#)
+        .unwrap()
+        .match_message(&message)
+        .unwrap());
+
+    assert!(matches!(
+        parse(r#"(message.text eq "a\u{110000}")"#),
+        Err(Error::InvalidEscape { position }) if position.begin == 19 && position.column == 20
+    ));
+    assert!(matches!(
+        parse(r#"(message.text eq "a\u41")"#),
+        Err(Error::InvalidEscape { position }) if position.begin == 19 && position.column == 20
+    ));
+    assert!(matches!(
+        parse(r#"(message.text eq "a\u{}")"#),
+        Err(Error::InvalidEscape { position }) if position.begin == 19 && position.column == 20
+    ));
+}
+
+#[test]
+fn test_parse_recovering() {
+    use matchingram::error::Error;
+    use matchingram::{compile_rule, compile_rule_recovering, Matcher};
+
+    let columns = |rule: &str| {
+        Matcher::from_rule_recovering(rule)
+            .unwrap_err()
+            .iter()
+            .map(|e| e.span().unwrap().begin)
+            .collect::<Vec<_>>()
+    };
+
+    // 条件的编译错误无需跳过，同一组中的全部错误都会被报告。
+    let rule = r#"(message.txt eq "a" or message.text eqq "b") or (message.from.id eq "x")"#;
+    let errors = Matcher::from_rule_recovering(rule).unwrap_err();
+    assert_eq!(3, errors.len());
+    assert!(matches!(errors[0].inner(), Error::UnknownField { .. }));
+    assert!(matches!(errors[1].inner(), Error::UnknownOperator { .. }));
+    assert!(matches!(errors[2].inner(), Error::NotAnInteger { .. }));
+
+    // 语法错误跳过到同一层级的 `or` 或 `)`。
+    let rule = r#"(message.text eq "a" and ( ) and (message.from.id eq "x")) or (not (message.text eqq "c") and message.txt)"#;
+    assert_eq!(vec![27, 53, 81, 94], columns(rule));
+
+    // 词法错误引起的语法错误不会被重复报告。
+    let rule = r#"(message.text eq 1.2.3 and message.from.id eq 1) or (message.text any {"a" @} and message.txt)"#;
+    assert_eq!(vec![17, 75, 82], columns(rule));
+
+    let rule = "(message.text eq \"abc\nand message.text eq \"b\") or (message.caption eq \"a\"";
+    assert_eq!(vec![17, 73], columns(rule));
+
+    // 没有错误时与 `from_rule` 一致，后者仍然在第一个错误处返回。
+    let rule = r#"(message.text eq "a") or (message.text eq "b")"#;
+    assert!(Matcher::from_rule_recovering(rule).is_ok());
+
+    let rule = r#"(message.txt eq "a") or (message.text eqq "b")"#;
+    assert_eq!(
+        Some(1),
+        Matcher::from_rule(rule)
+            .unwrap_err()
+            .span()
+            .map(|s| s.begin)
+    );
+
+    // 空的规则（或只有空白和注释的规则）缺少条件。
+    for rule in &["", "  \n ", "# 注释"] {
+        assert!(matches!(
+            compile_rule(*rule),
+            Err(Error::MissingCondition { .. })
+        ));
+
+        let errors = compile_rule_recovering(*rule).unwrap_err();
+        assert_eq!(1, errors.len());
+        assert!(matches!(errors[0], Error::MissingCondition { .. }));
+    }
+}
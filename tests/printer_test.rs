@@ -0,0 +1,151 @@
+use matchingram::compile_rule;
+use matchingram::matches::{Cont, Field, Matcher, Value};
+use matchingram::models::Message;
+use matchingram::operator::Operator;
+use matchingram::printer::Printer;
+
+#[test]
+fn test_display() {
+    let rules = vec![
+        r#"(message.from.is_bot)"#,
+        r#"(not message.from.is_bot and message.text.len gt 120)"#,
+        r#"(message.from.id eq -10086) or (message.location.latitude le -0.5)"#,
+        r#"(message.location.longitude ge 120.0)"#,
+        r#"(message.text any.nfkc.i {"bot" "机器人"}) or (message.text re "^[0-9]+$")"#,
+        r#"(message.from.is_bot and (message.text hd "a" or not (message.text td "b")))"#,
+        r#"(message.from.is_bot or message.text eq "a") or (not (message.text eq "b"))"#,
+        r#"(message.text in {"a"} and message.text re_any {"a" "b"})"#,
+        r#"(message.text re "^\\d+$") or (message.text eq "say \"hi\"\n\t\\")"#,
+        r#"(message.text eq "") or (message.text any {"" "a"})"#,
+    ];
+
+    for rule in rules {
+        let matcher = compile_rule(rule).unwrap();
+
+        assert_eq!(matcher.to_string(), rule);
+        assert_eq!(compile_rule(matcher.to_string()).unwrap().to_string(), rule);
+    }
+
+    // 非规范的规则会被规范化。
+    let matcher = compile_rule(
+        r#"
+        message.text any.nfkc.i.i {"a"}   and
+        ( message.from.is_bot or message.text.len gt 3)
+        "#,
+    )
+    .unwrap();
+    assert_eq!(
+        matcher.to_string(),
+        r#"(message.text any.nfkc.i {"a"} and (message.from.is_bot or message.text.len gt 3))"#
+    );
+
+    // 未知的转义序列保持原样，输出时反斜杠会被转义。
+    let matcher = compile_rule(r#"(message.text re "^\d+$")"#).unwrap();
+    assert_eq!(matcher.to_string(), r#"(message.text re "^\\d+$")"#);
+
+    // 手动创建的条件组。
+    let groups = vec![
+        vec![
+            Cont::build(
+                false,
+                Field::MessageText,
+                Operator::Any,
+                vec![Value::from_str("柬埔寨"), Value::from_str("东南亚")],
+            )
+            .unwrap(),
+            Cont::build(
+                true,
+                Field::MessageTextLen,
+                Operator::Gt,
+                vec![Value::Integer(5)],
+            )
+            .unwrap(),
+        ],
+        vec![Cont::single_field(false, "message.from.is_bot".to_owned()).unwrap()],
+    ];
+    let matcher = Matcher::new(groups);
+    let rule = r#"(message.text any {"柬埔寨" "东南亚"} and not message.text.len gt 5) or (message.from.is_bot)"#;
+    assert_eq!(matcher.to_string(), rule);
+
+    let message = Message {
+        text: Some(format!("东南亚")),
+        ..Default::default()
+    };
+    assert_eq!(
+        matcher.match_message(&message).unwrap(),
+        compile_rule(rule).unwrap().match_message(&message).unwrap()
+    );
+
+    // 手动创建的空字符串同样可以重新编译。
+    let cont = Cont::build(
+        false,
+        Field::MessageText,
+        Operator::Eq,
+        vec![Value::Letter(String::new())],
+    )
+    .unwrap();
+    let matcher = Matcher::new(vec![vec![cont]]);
+    assert_eq!(matcher.to_string(), r#"(message.text eq "")"#);
+    assert_eq!(
+        compile_rule(matcher.to_string()).unwrap().to_string(),
+        matcher.to_string()
+    );
+
+    assert_eq!(Value::Decimal(1.0).to_string(), "1.0");
+    assert_eq!(Value::Decimal(-0.25).to_string(), "-0.25");
+    assert_eq!(
+        Value::from_str("\"a\"\\\n\t").to_string(),
+        r#""\"a\"\\\n\t""#
+    );
+}
+
+#[test]
+fn test_printer() {
+    let rule = r#"
+        (message.text.len gt 120 and message.from.is_bot) or
+        (not message.from.is_bot and message.from.full_name any {"bot" "机器人"}) or
+        (not message.from.id eq 10086 and message.text any {"移动" "联通"} and (message.text any {"我是" "客服"} or message.text.len lt 10))
+    "#;
+    let matcher = compile_rule(rule).unwrap();
+
+    let printed = Printer::new().print(&matcher);
+    assert_eq!(
+        printed,
+        r#"(message.text.len gt 120 and message.from.is_bot) or
+(not message.from.is_bot and message.from.full_name any {"bot" "机器人"}) or
+(
+  not message.from.id eq 10086 and
+  message.text any {"移动" "联通"} and
+  (message.text any {"我是" "客服"} or message.text.len lt 10)
+)"#
+    );
+    assert_eq!(
+        compile_rule(printed).unwrap().to_string(),
+        matcher.to_string()
+    );
+
+    let printed = Printer::new().indent(4).max_width(40).print(&matcher);
+    assert_eq!(
+        printed,
+        r#"(
+    message.text.len gt 120 and
+    message.from.is_bot
+) or
+(
+    not message.from.is_bot and
+    message.from.full_name any {"bot" "机器人"}
+) or
+(
+    not message.from.id eq 10086 and
+    message.text any {"移动" "联通"} and
+    (
+        message.text any {"我是" "客服"} or
+        message.text.len lt 10
+    )
+)"#
+    );
+    assert_eq!(
+        compile_rule(printed).unwrap().to_string(),
+        matcher.to_string()
+    );
+}
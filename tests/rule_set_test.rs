@@ -0,0 +1,108 @@
+use matchingram::models::Message;
+use matchingram::{Error, RuleSet};
+
+#[test]
+fn test_rule_set() {
+    let json_data = r#"
+        {
+            "text": "/start 欢迎加入 ＢＯＴ 交流群",
+            "entities": [{ "type": "bot_command", "offset": 0, "length": 6 }],
+            "from": {
+                "id": 10000,
+                "first_name": "Rust",
+                "is_bot": false
+            }
+        }
+    "#;
+    let message = serde_json::from_str::<Message>(json_data).unwrap();
+
+    let mut rule_set = RuleSet::new();
+    assert!(rule_set.is_empty());
+
+    rule_set
+        .add(1, r#"(message.text any.i.nfkc {"bot"})"#)
+        .unwrap();
+    rule_set.add(2, r#"(message.from.is_bot)"#).unwrap();
+    rule_set
+        .add_with_priority(3, 10, r#"(message.is_command)"#)
+        .unwrap();
+    rule_set
+        .add_with_priority(4, -1, r#"(message.text hd.nfkc "/start")"#)
+        .unwrap();
+    rule_set
+        .add(5, r#"(message.text td.nfkc.i "交流群")"#)
+        .unwrap();
+    assert_eq!(rule_set.len(), 5);
+
+    assert_eq!(rule_set.matches(&message).unwrap(), vec![&3, &1, &5, &4]);
+    assert_eq!(rule_set.first_match(&message).unwrap(), Some(&3));
+
+    let message = Message::default();
+    assert!(rule_set.matches(&message).unwrap().is_empty());
+    assert_eq!(rule_set.first_match(&message).unwrap(), None);
+
+    assert!(matches!(
+        rule_set.add(6, r#"(message.text any)"#),
+        Err(Error::MissingValue { .. })
+    ));
+    assert_eq!(rule_set.len(), 5);
+}
+
+#[test]
+fn test_rule_set_shared_fields() {
+    // 同一次匹配中的规则共享解析出的字段，不同字段和不同消息的缓存互不干扰。
+    let message: Message = serde_json::from_str(
+        r#"
+        {
+            "text": "访问 https://t.me/spam 和 #福利",
+            "entities": [
+                {"type": "url", "offset": 3, "length": 17},
+                {"type": "hashtag", "offset": 23, "length": 3}
+            ],
+            "from": {"id": 1, "first_name": "Spam", "last_name": "Bot", "is_bot": false},
+            "reply_to_message": {
+                "text": "https://example.com",
+                "from": {"id": 2, "first_name": "小明", "is_bot": false}
+            }
+        }
+    "#,
+    )
+    .unwrap();
+
+    let mut rule_set = RuleSet::new();
+    rule_set
+        .add("url", r#"(message.entities.url eq "https://t.me/spam")"#)
+        .unwrap();
+    rule_set
+        .add("hashtag", r##"(message.entities.hashtag eq "#福利")"##)
+        .unwrap();
+    rule_set
+        .add("host", r#"(message.urls.host eq "t.me")"#)
+        .unwrap();
+    rule_set
+        .add("path", r#"(message.urls.path eq "/spam")"#)
+        .unwrap();
+    rule_set
+        .add("name", r#"(message.from.full_name eq "SpamBot")"#)
+        .unwrap();
+    rule_set
+        .add("name.i", r#"(message.from.full_name any.i {"spambot"})"#)
+        .unwrap();
+    rule_set
+        .add(
+            "reply",
+            r#"(message.reply_to_message.urls.host eq "example.com")"#,
+        )
+        .unwrap();
+    rule_set
+        .add(
+            "reply.name",
+            r#"(message.reply_to_message.from.full_name eq "SpamBot")"#,
+        )
+        .unwrap();
+
+    assert_eq!(
+        rule_set.matches(&message).unwrap(),
+        vec![&"url", &"hashtag", &"host", &"path", &"name", &"name.i", &"reply"]
+    );
+}
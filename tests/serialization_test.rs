@@ -0,0 +1,85 @@
+#![cfg(feature = "serialize")]
+
+use matchingram::models::Message;
+use matchingram::{compile_rule, Matcher};
+
+#[test]
+fn test_serialize_matcher() {
+    let rule = r#"(message.text any.i {"bot" "机器人"} and not message.from.is_bot) or (not (message.location.latitude gt 0.5 or message.text re "^[0-9]+$"))"#;
+    let matcher = compile_rule(rule).unwrap();
+
+    let json = serde_json::to_string(&matcher).unwrap();
+    assert_eq!(
+        json,
+        r#"{"version":1,"expr":{"or":[{"and":[{"cont":{"field":"message.text","operator":"any","modifiers":["i"],"value":["bot","机器人"]}},{"cont":{"negative":true,"field":"message.from.is_bot"}}]},{"not":{"or":[{"cont":{"field":"message.location.latitude","operator":"gt","value":[0.5]}},{"cont":{"field":"message.text","operator":"re","value":["^[0-9]+$"]}}]}}]}}"#
+    );
+
+    let loaded = serde_json::from_str::<Matcher>(&json).unwrap();
+    assert_eq!(loaded.to_string(), rule);
+
+    let message = Message {
+        text: Some(format!("I am a BOT")),
+        ..Default::default()
+    };
+    assert!(loaded.match_message(&message).unwrap());
+
+    let message = Message {
+        text: Some(format!("12345")),
+        ..Default::default()
+    };
+    assert!(!loaded.match_message(&message).unwrap());
+}
+
+#[test]
+fn test_deserialize_matcher() {
+    let json = r#"{"version":1,"expr":{"cont":{"field":"message.from.id","operator":"eq","value":[10086]}}}"#;
+    let matcher = serde_json::from_str::<Matcher>(json).unwrap();
+    assert_eq!(matcher.to_string(), "(message.from.id eq 10086)");
+
+    let json = r#"{"version":1,"expr":{"cont":{"field":"message.location.latitude","operator":"eq","value":[1.0]}}}"#;
+    let matcher = serde_json::from_str::<Matcher>(json).unwrap();
+    assert_eq!(matcher.to_string(), "(message.location.latitude eq 1.0)");
+
+    let json = r#"{"version":1,"expr":{"cont":{"field":"message.from.is_bot","operator":"eq","value":[false]}}}"#;
+    let matcher = serde_json::from_str::<Matcher>(json).unwrap();
+    assert_eq!(matcher.to_string(), "(message.from.is_bot eq false)");
+    assert_eq!(serde_json::to_string(&matcher).unwrap(), json);
+
+    let json = r#"{"version":1,"expr":{"cont":{"field":"message.from.id","operator":"in","value":[1,{"start":10,"end":20}]}}}"#;
+    let matcher = serde_json::from_str::<Matcher>(json).unwrap();
+    assert_eq!(matcher.to_string(), "(message.from.id in {1 10..20})");
+    assert_eq!(serde_json::to_string(&matcher).unwrap(), json);
+
+    let json = r#"{"version":1,"expr":{"cont":{"field":"message.text","operator":"any","modifiers":["i"],"value":[{"name":"spam_words"}]}}}"#;
+    let matcher = serde_json::from_str::<Matcher>(json).unwrap();
+    assert_eq!(matcher.to_string(), "(message.text any.i $spam_words)");
+    assert_eq!(serde_json::to_string(&matcher).unwrap(), json);
+
+    let json = r#"{"version":1,"expr":{"and":[{"cont":{"field":"message.reply_to_message.from.is_bot"}},{"cont":{"field":"message.pinned_message.text","operator":"hd","value":["/"]}}]}}"#;
+    let matcher = serde_json::from_str::<Matcher>(json).unwrap();
+    assert_eq!(
+        matcher.to_string(),
+        r#"(message.reply_to_message.from.is_bot and message.pinned_message.text hd "/")"#
+    );
+    assert_eq!(serde_json::to_string(&matcher).unwrap(), json);
+
+    let json = r#"{"version":2,"expr":{"cont":{"field":"message.from.is_bot"}}}"#;
+    let err = serde_json::from_str::<Matcher>(json).unwrap_err();
+    assert!(err.to_string().contains("unsupported matcher version `2`"));
+
+    let json = r#"{"version":1,"expr":{"cont":{"field":"message.from.is_human"}}}"#;
+    let err = serde_json::from_str::<Matcher>(json).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("unknown `message.from.is_human` field"));
+
+    // 反序列化时同样会检查条件。
+    let json = r#"{"version":1,"expr":{"cont":{"field":"message.from.id","operator":"any","value":["1"]}}}"#;
+    let err = serde_json::from_str::<Matcher>(json).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("does not support the `any` operator"));
+
+    let json = r#"{"version":1,"expr":{"cont":{"field":"message.text","operator":"re","value":["[a-z"]}}}"#;
+    assert!(serde_json::from_str::<Matcher>(json).is_err());
+}